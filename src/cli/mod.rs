@@ -0,0 +1,431 @@
+use std::{error::Error, fs, path::{Path, PathBuf}, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand};
+
+use code_scan::{enums::{CommentHits, Framwork, LangType, ORM, Severity}, model::baseline::BaselineState, report::JsonReport, util::rule_lint::{LintLevel, RuleLint}, Config, ScanResult, ScannerRegistry};
+
+// 存在不低于fail_on严重程度的风险代码时的退出码
+pub const EXIT_FINDINGS: u8 = 1;
+// 配置错误、文件读写失败等导致扫描无法完成，或部分文件被跳过、分析阶段失败时的退出码
+pub const EXIT_SCAN_ERROR: u8 = 2;
+
+/**
+ * 命令行入口参数
+ * @Field command 子命令，未填写时等同于scan
+ */
+#[derive(Parser, Debug)]
+#[command(name = "code_scan", version, about = "代码审计协助工具", long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/**
+ * 子命令
+ * @Enum Scan 扫描项目并生成报告
+ * @Enum Rules 规则相关操作
+ * @Enum Config 配置文件相关操作
+ * @Enum Schema 输出JSON报告的JSON Schema
+ */
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// 扫描项目代码并生成报告
+    Scan(ScanArgs),
+    /// 规则相关操作
+    Rules {
+        #[command(subcommand)]
+        command: RulesCommand,
+    },
+    /// 配置文件相关操作
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// 输出JSON报告的JSON Schema
+    Schema {
+        /// schema输出路径，默认输出到控制台
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RulesCommand {
+    /// 列出本次配置会加载的规则
+    List(ConfigArgs),
+    /// 检查规则的正则、重复和过于宽泛的关键字，并执行规则示例
+    Lint(LintArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// 校验配置文件是否可用
+    Validate(ConfigArgs),
+}
+
+/**
+ * 配置文件路径以及可以覆盖配置文件的参数，未填写的参数以配置文件为准
+ * @Field config 配置文件路径，默认读取程序同级目录的config.json/config.yaml/config.yml
+ */
+#[derive(Args, Debug, Default)]
+pub struct ConfigArgs {
+    /// 配置文件路径(JSON或YAML)，默认读取程序同级目录的config.json/config.yaml/config.yml
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+
+    /// 覆盖配置中的project_name
+    #[arg(long)]
+    pub project_name: Option<String>,
+
+    /// 覆盖配置中的lang_type，可选值为已注册的语言，如java、php、go
+    #[arg(long)]
+    pub lang: Option<LangType>,
+
+    /// 覆盖配置中的work_dir
+    #[arg(short, long)]
+    pub work_dir: Option<String>,
+
+    /// 覆盖配置中的scan_ext，多个后缀以逗号分隔
+    #[arg(long, value_delimiter = ',')]
+    pub ext: Option<Vec<String>>,
+
+    /// 覆盖配置中的exclude_path，多个目录以逗号分隔
+    #[arg(long, value_delimiter = ',')]
+    pub exclude: Option<Vec<String>>,
+
+    /// 覆盖配置中的framework，可选值为扫描器支持的框架，如spring、struts、struts2、laravel、thinkphp、none
+    #[arg(long)]
+    pub framework: Option<Framwork>,
+
+    /// 覆盖配置中的orm
+    #[arg(long, value_enum, ignore_case = true)]
+    pub orm: Option<ORM>,
+
+    /// 覆盖配置中的depency_file
+    #[arg(long)]
+    pub depency_file: Option<String>,
+
+    /// 追加到配置中的rule_paths，多个规则文件或目录以逗号分隔
+    #[arg(long, value_delimiter = ',')]
+    pub rules: Option<Vec<String>>,
+
+    /// 追加到配置中的disabled_rules，多个规则id以逗号分隔
+    #[arg(long, value_delimiter = ',')]
+    pub disable_rule: Option<Vec<String>>,
+}
+
+/**
+ * scan子命令参数
+ * @Field config 配置相关参数
+ * @Field out 报告输出路径
+ * @Field sarif SARIF报告输出路径
+ * @Field json JSON报告输出路径
+ * @Field html HTML报告输出路径
+ * @Field context_lines HTML报告中的上下文行数
+ * @Field fail_on 导致非0退出的最低严重程度
+ * @Field comment_hits 规则只在注释中命中时的处理方式
+ * @Field baseline 对比使用的基线文件
+ * @Field save_baseline 保存本次扫描结果为基线的路径
+ * @Field force 报告已存在时是否覆盖
+ */
+#[derive(Args, Debug, Default)]
+pub struct ScanArgs {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    /// 覆盖配置中的report_file_path
+    #[arg(short, long)]
+    pub out: Option<String>,
+
+    /// 覆盖配置中的sarif_file_path，输出SARIF 2.1.0格式的报告
+    #[arg(long)]
+    pub sarif: Option<String>,
+
+    /// 覆盖配置中的json_file_path，输出结构化的JSON报告
+    #[arg(long)]
+    pub json: Option<String>,
+
+    /// 覆盖配置中的html_file_path，输出可离线查看的HTML报告
+    #[arg(long)]
+    pub html: Option<String>,
+
+    /// 覆盖配置中的context_lines，HTML报告中命中行前后展示的源码行数
+    #[arg(long)]
+    pub context_lines: Option<usize>,
+
+    /// 覆盖配置中的fail_on，存在不低于该严重程度的风险代码时以状态码1退出
+    #[arg(long, value_enum, ignore_case = true)]
+    pub fail_on: Option<Severity>,
+
+    /// 覆盖配置中的comment_hits，规则只在注释中命中时跳过(skip)或降为info(downgrade)
+    #[arg(long, value_enum, ignore_case = true)]
+    pub comment_hits: Option<CommentHits>,
+
+    /// 覆盖配置中的baseline_file_path，与基线对比后只详细报告新增的风险代码
+    #[arg(long)]
+    pub baseline: Option<String>,
+
+    /// 覆盖配置中的save_baseline_path，将本次扫描结果保存为基线
+    #[arg(long)]
+    pub save_baseline: Option<String>,
+
+    /// 报告文件已存在时直接覆盖
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+/**
+ * rules lint子命令参数
+ * @Field config 配置相关参数
+ * @Field deny_warnings 存在警告时是否也以非0状态码退出
+ */
+#[derive(Args, Debug, Default)]
+pub struct LintArgs {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    /// 存在警告时也以非0状态码退出
+    #[arg(long)]
+    pub deny_warnings: bool,
+}
+
+impl ConfigArgs {
+
+    /**
+     * @descript 读取配置文件并使用命令行参数覆盖对应字段
+     * @return 覆盖后的Config对象
+     */
+    pub fn load_config(&self) -> Result<Config, Box<dyn Error>> {
+        let mut config = Config::read_config_by_file(self.config.as_deref())?;
+        if let Some(project_name) = &self.project_name {
+            config.project_name = project_name.clone();
+        }
+        if let Some(lang) = &self.lang {
+            config.lang_type = lang.clone();
+        }
+        if let Some(work_dir) = &self.work_dir {
+            config.work_dir = work_dir.clone();
+        }
+        if let Some(ext) = &self.ext {
+            config.scan_ext = ext.clone();
+        }
+        if let Some(exclude) = &self.exclude {
+            config.exclude_path = exclude.clone();
+        }
+        if let Some(framework) = &self.framework {
+            config.framework = framework.clone();
+        }
+        if let Some(orm) = &self.orm {
+            config.orm = orm.clone();
+        }
+        if let Some(depency_file) = &self.depency_file {
+            config.depency_file = depency_file.clone();
+        }
+        if let Some(rules) = &self.rules {
+            config.rule_paths.extend(rules.iter().cloned());
+        }
+        if let Some(disable_rule) = &self.disable_rule {
+            config.disabled_rules.extend(disable_rule.iter().cloned());
+        }
+        Ok(config)
+    }
+}
+
+impl Cli {
+
+    /**
+     * @descript 根据子命令执行对应的功能
+     * @return 进程退出码
+     */
+    pub fn run(self) -> Result<ExitCode, Box<dyn Error>> {
+        match self.command {
+            Some(Command::Scan(args)) => Self::scan(&args),
+            Some(Command::Rules { command: RulesCommand::List(args) }) => Self::list_rules(&args).map(|_| ExitCode::SUCCESS),
+            Some(Command::Rules { command: RulesCommand::Lint(args) }) => Self::lint_rules(&args).map(|_| ExitCode::SUCCESS),
+            Some(Command::Config { command: ConfigCommand::Validate(args) }) => Self::validate_config(&args).map(|_| ExitCode::SUCCESS),
+            Some(Command::Schema { out }) => Self::print_schema(out.as_deref()).map(|_| ExitCode::SUCCESS),
+            // 兼容旧的使用方式，直接运行等同于scan
+            None => Self::scan(&ScanArgs::default()),
+        }
+    }
+
+    /**
+     * @descript 扫描项目并生成报告
+     * @param args scan子命令参数
+     * @return 存在不低于fail_on的风险代码时返回EXIT_FINDINGS，有文件被跳过或分析阶段失败时返回EXIT_SCAN_ERROR
+     */
+    fn scan(args: &ScanArgs) -> Result<ExitCode, Box<dyn Error>> {
+        let mut config = args.config.load_config()?;
+        if let Some(out) = &args.out {
+            config.report_file_path = out.clone();
+        }
+        if let Some(sarif) = &args.sarif {
+            config.sarif_file_path = Some(sarif.clone());
+        }
+        if let Some(json) = &args.json {
+            config.json_file_path = Some(json.clone());
+        }
+        if let Some(html) = &args.html {
+            config.html_file_path = Some(html.clone());
+        }
+        if let Some(context_lines) = args.context_lines {
+            config.context_lines = context_lines;
+        }
+        if let Some(fail_on) = args.fail_on {
+            config.fail_on = Some(fail_on);
+        }
+        if let Some(comment_hits) = args.comment_hits {
+            config.comment_hits = comment_hits;
+        }
+        if let Some(baseline) = &args.baseline {
+            config.baseline_file_path = Some(baseline.clone());
+        }
+        if let Some(save_baseline) = &args.save_baseline {
+            config.save_baseline_path = Some(save_baseline.clone());
+        }
+        // 输出报告的文件，md格式以及配置的sarif、json、html格式
+        let report_files = [Some(&config.report_file_path), config.sarif_file_path.as_ref(), config.json_file_path.as_ref(), config.html_file_path.as_ref()];
+        if let Some(existing) = report_files.into_iter().flatten().find(|path| Path::new(path).exists()) {
+            if !args.force {
+                return Err(format!("[-]报告文件: {} 已经存在,可使用--force覆盖", existing).into());
+            }
+        }
+        let fail_on = config.fail_on;
+        // 根据语言加载
+        let scan_result = code_scan::scan(config)?;
+        Ok(Self::summary(&scan_result, fail_on))
+    }
+
+    /**
+     * @descript 输出各严重程度的风险代码数量，并根据fail_on决定退出码，指定了基线时只有新增的风险代码会导致非0退出
+     * 有文件处理失败被跳过或分析阶段失败时扫描结果不完整，优先以EXIT_SCAN_ERROR退出，警告不影响退出码
+     * @param scan_result 扫描结果
+     * @param fail_on 导致非0退出的最低严重程度
+     */
+    fn summary(scan_result: &ScanResult, fail_on: Option<Severity>) -> ExitCode {
+        let counts: Vec<String> = Severity::DESCENDING.iter().map(|severity| {
+            let count = scan_result.findings.iter().filter(|finding| finding.severity == *severity).count();
+            format!("{}: {}", severity.as_str(), count)
+        }).collect();
+        println!("[*]风险代码共计{}处 [{}]", scan_result.findings.len(), counts.join(", "));
+        if let Some(diff) = &scan_result.baseline {
+            println!("[*]与基线对比: 新增{}处, 未变化{}处, 已修复{}处", diff.new, diff.unchanged, diff.fixed.len());
+        }
+        let mut exit_code = ExitCode::SUCCESS;
+        if let Some(fail_on) = fail_on {
+            let failed = scan_result.findings.iter()
+                .filter(|finding| finding.baseline_state != Some(BaselineState::Unchanged))
+                .filter(|finding| finding.severity >= fail_on)
+                .count();
+            if failed > 0 {
+                println!("[-]存在{}处严重程度不低于{}的风险代码", failed, fail_on.as_str());
+                exit_code = ExitCode::from(EXIT_FINDINGS);
+            }
+        }
+        if !scan_result.errors.is_empty() {
+            println!("[-]扫描中有{}处处理失败(跳过的文件或失败的分析阶段),扫描结果不完整", scan_result.errors.len());
+            exit_code = ExitCode::from(EXIT_SCAN_ERROR);
+        }
+        exit_code
+    }
+
+    /**
+     * @descript 输出JSON报告的JSON Schema
+     * @param out 输出路径，为空时输出到控制台
+     */
+    fn print_schema(out: Option<&Path>) -> Result<(), Box<dyn Error>> {
+        let schema = serde_json::to_string_pretty(&JsonReport::schema())?;
+        match out {
+            Some(path) => fs::write(path, schema)?,
+            None => println!("{}", schema),
+        }
+        Ok(())
+    }
+
+    /**
+     * @descript 输出本次配置会加载的规则
+     * @param args 配置相关参数
+     */
+    fn list_rules(args: &ConfigArgs) -> Result<(), Box<dyn Error>> {
+        let mut config = args.load_config()?;
+        let registry = ScannerRegistry::default();
+        let scanner = registry.get(&config.lang_type)?;
+        scanner.prepare(&mut config)?;
+        config.load_rule(&scanner.default_rule_pack(), &scanner.frameworks())?;
+        for (index, rule) in config.rule.iter().enumerate() {
+            println!("{:>4}. [{:?}][{}] {} => {}", index + 1, rule.condition, rule.scope, rule.keyword, rule.note);
+        }
+        Ok(())
+    }
+
+    /**
+     * @descript 检查本次配置会加载的规则，所有问题一次性输出
+     * @param args rules lint子命令参数
+     */
+    fn lint_rules(args: &LintArgs) -> Result<(), Box<dyn Error>> {
+        let mut config = args.config.load_config()?;
+        let registry = ScannerRegistry::default();
+        let scanner = registry.get(&config.lang_type)?;
+        scanner.prepare(&mut config)?;
+        // 重复的规则在合并时会被忽略或覆盖，需要检查合并前的规则
+        let sources = config.read_rule_sources(&scanner.default_rule_pack(), &scanner.frameworks())?;
+        config.merge_rules(sources.clone());
+        let example_ext = scanner.default_exts().into_iter().next().unwrap_or_default();
+        let result = RuleLint::lint(&config, &sources, &example_ext);
+        for issue in &result.issues {
+            match issue.level {
+                LintLevel::Error => println!("[-]规则[{}] 错误: {}", issue.rule_id, issue.message),
+                LintLevel::Warning => println!("[*]规则[{}] 警告: {}", issue.rule_id, issue.message),
+            }
+        }
+        let errors = result.count(LintLevel::Error);
+        let warnings = result.count(LintLevel::Warning);
+        println!("[*]共检查规则{}条,错误{}处,警告{}处,示例通过{}/{}", config.rule.len(), errors, warnings, result.passed, result.examples);
+        if errors > 0 || (args.deny_warnings && warnings > 0) {
+            return Err(format!("[-]规则检查未通过,错误{}处,警告{}处", errors, warnings).into());
+        }
+        println!("[+]规则检查通过");
+        Ok(())
+    }
+
+    /**
+     * @descript 校验配置文件，所有问题一次性输出
+     * @param args 配置相关参数
+     */
+    fn validate_config(args: &ConfigArgs) -> Result<(), Box<dyn Error>> {
+        let mut config = args.load_config()?;
+        let mut problems: Vec<String> = vec![];
+        if !Path::new(&config.work_dir).is_dir() {
+            problems.push(format!("work_dir: {} 不是一个存在的目录", config.work_dir));
+        }
+        let registry = ScannerRegistry::default();
+        match registry.get(&config.lang_type) {
+            Ok(scanner) => {
+                if let Err(err) = scanner.prepare(&mut config) {
+                    problems.push(err.to_string().trim_start_matches("[-]").to_string());
+                }
+                if let Err(err) = config.load_rule(&scanner.default_rule_pack(), &scanner.frameworks()) {
+                    problems.push(format!("规则加载失败: {}", err));
+                }
+            },
+            Err(err) => problems.push(err.to_string().trim_start_matches("[-]").to_string()),
+        }
+        if config.scan_ext.is_empty() {
+            problems.push("scan_ext 不能为空".to_string());
+        }
+        if config.unauthorized.valid && config.unauthorized.prefix.is_empty() {
+            problems.push("unauthorized.valid为true时unauthorized.prefix不能为空".to_string());
+        }
+        if !config.depency_file.is_empty() && !Path::new(&config.depency_file).is_file() {
+            println!("[*]依赖文件: {} 不存在,扫描时将跳过依赖收集", config.depency_file);
+        }
+        if problems.is_empty() {
+            println!("[+]配置校验通过,共计规则{}条", config.rule.len());
+            return Ok(())
+        }
+        for problem in &problems {
+            println!("[-]{}", problem);
+        }
+        Err(format!("[-]配置校验失败,共{}处问题", problems.len()).into())
+    }
+}
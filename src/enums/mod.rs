@@ -1,20 +1,94 @@
-use serde::Deserialize;
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use clap::ValueEnum;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{Deserialize, Serialize};
 
 /**
- * 扫描的语言类型枚举
+ * 扫描的语言类型枚举，配置中填写语言名，不区分大小写
+ * @Enum Other 通过ScannerRegistry注册的其他语言
  */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+#[allow(clippy::upper_case_acronyms)]
 pub enum LangType {
 
    JAVA,
    PHP,
-   GO
+   GO,
+   Other(String)
+
+}
+
+impl LangType {
+
+   /**
+    * @descript 判断是否为同一种语言，语言名不区分大小写
+    */
+   pub fn is(&self, other: &LangType) -> bool {
+      self.to_string().eq_ignore_ascii_case(&other.to_string())
+   }
+}
+
+impl From<String> for LangType {
+
+   fn from(value: String) -> Self {
+      match value.trim().to_uppercase().as_str() {
+         "JAVA" => LangType::JAVA,
+         "PHP" => LangType::PHP,
+         "GO" => LangType::GO,
+         _ => LangType::Other(value.trim().to_string()),
+      }
+   }
+
+}
+
+impl FromStr for LangType {
+
+   type Err = Infallible;
+
+   fn from_str(value: &str) -> Result<Self, Self::Err> {
+      Ok(LangType::from(value.to_string()))
+   }
+
+}
+
+impl fmt::Display for LangType {
+
+   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      match self {
+         LangType::JAVA => write!(f, "JAVA"),
+         LangType::PHP => write!(f, "PHP"),
+         LangType::GO => write!(f, "GO"),
+         LangType::Other(name) => write!(f, "{}", name),
+      }
+   }
+
+}
+
+impl From<LangType> for String {
+
+   fn from(lang_type: LangType) -> Self {
+      lang_type.to_string()
+   }
+
+}
+
+impl JsonSchema for LangType {
+
+   fn schema_name() -> String {
+      "LangType".to_string()
+   }
+
+   fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+      String::json_schema(gen)
+   }
 
 }
 /**
  * 规则文件规则类型枚举
  */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
 pub enum RuleCondition {
 
    Contain,
@@ -23,9 +97,72 @@ pub enum RuleCondition {
 }
 
 /**
- * 开发框架枚举
+ * 规则匹配范围枚举，配置中写作line、file或window(n)
+ * @Enum Line 逐行匹配
+ * @Enum File 在整个文件内容上匹配，可以跨行
+ * @Enum Window 在连续n行拼接的内容上匹配，可以跨行
  */
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(try_from = "String", into = "String")]
+pub enum RuleScope {
+
+   #[default]
+   Line,
+   File,
+   Window(usize)
+
+}
+
+impl TryFrom<String> for RuleScope {
+
+   type Error = String;
+
+   fn try_from(value: String) -> Result<Self, Self::Error> {
+      let scope = value.trim();
+      match scope {
+         "line" => Ok(RuleScope::Line),
+         "file" => Ok(RuleScope::File),
+         _ => {
+            let lines = scope.strip_prefix("window(")
+               .and_then(|rest| rest.strip_suffix(')'))
+               .and_then(|lines| lines.trim().parse::<usize>().ok());
+            match lines {
+               Some(lines) if lines >= 2 => Ok(RuleScope::Window(lines)),
+               _ => Err(format!("scope: {} 不合法，可选值为line、file或window(n)，n不小于2", value)),
+            }
+         },
+      }
+   }
+
+}
+
+impl fmt::Display for RuleScope {
+
+   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      match self {
+         RuleScope::Line => write!(f, "line"),
+         RuleScope::File => write!(f, "file"),
+         RuleScope::Window(lines) => write!(f, "window({})", lines),
+      }
+   }
+
+}
+
+impl From<RuleScope> for String {
+
+   fn from(scope: RuleScope) -> Self {
+      scope.to_string()
+   }
+
+}
+
+/**
+ * 开发框架枚举，配置中填写框架名，不区分大小写
+ * @Enum Other 注册的扫描器支持的其他框架
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Framwork {
 
    Spring,
@@ -34,7 +171,86 @@ pub enum Framwork {
    Laravel,
    ThinkPhp,
    YII,
-   None
+   None,
+   Other(String)
+
+}
+
+impl Framwork {
+
+   // 内置的开发框架，规则中还可以填写扫描器注册的其他框架
+   pub const BUILTIN: [Framwork; 6] = [Framwork::Spring, Framwork::Struts, Framwork::Struts2, Framwork::Laravel, Framwork::ThinkPhp, Framwork::YII];
+
+   /**
+    * @descript 判断是否为同一个框架，框架名不区分大小写
+    */
+   pub fn is(&self, other: &Framwork) -> bool {
+      self.to_string().eq_ignore_ascii_case(&other.to_string())
+   }
+}
+
+impl From<String> for Framwork {
+
+   fn from(value: String) -> Self {
+      // 兼容命令行中think-php形式的写法
+      match value.trim().to_lowercase().replace('-', "").as_str() {
+         "spring" => Framwork::Spring,
+         "struts" => Framwork::Struts,
+         "struts2" => Framwork::Struts2,
+         "laravel" => Framwork::Laravel,
+         "thinkphp" => Framwork::ThinkPhp,
+         "yii" => Framwork::YII,
+         "none" => Framwork::None,
+         _ => Framwork::Other(value.trim().to_string()),
+      }
+   }
+
+}
+
+impl FromStr for Framwork {
+
+   type Err = Infallible;
+
+   fn from_str(value: &str) -> Result<Self, Self::Err> {
+      Ok(Framwork::from(value.to_string()))
+   }
+
+}
+
+impl fmt::Display for Framwork {
+
+   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      match self {
+         Framwork::Spring => write!(f, "Spring"),
+         Framwork::Struts => write!(f, "Struts"),
+         Framwork::Struts2 => write!(f, "Struts2"),
+         Framwork::Laravel => write!(f, "Laravel"),
+         Framwork::ThinkPhp => write!(f, "ThinkPhp"),
+         Framwork::YII => write!(f, "YII"),
+         Framwork::None => write!(f, "None"),
+         Framwork::Other(name) => write!(f, "{}", name),
+      }
+   }
+
+}
+
+impl From<Framwork> for String {
+
+   fn from(framework: Framwork) -> Self {
+      framework.to_string()
+   }
+
+}
+
+impl JsonSchema for Framwork {
+
+   fn schema_name() -> String {
+      "Framwork".to_string()
+   }
+
+   fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+      String::json_schema(gen)
+   }
 
 }
 
@@ -42,11 +258,314 @@ pub enum Framwork {
 /**
  * ORM框架枚举
  */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, ValueEnum)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum ORM {
 
    Mybatis,
    Hibernate,
    None
 
-}
\ No newline at end of file
+}
+
+/**
+ * 规则适用的框架，可以填写开发框架或ORM框架的名称，不区分大小写
+ * 开发框架可以是任意注册的框架名，需要先尝试匹配ORM框架
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+pub enum FrameworkFilter {
+
+   Orm(ORM),
+   Framework(Framwork)
+
+}
+
+impl From<String> for FrameworkFilter {
+
+   fn from(value: String) -> Self {
+      match <ORM as ValueEnum>::from_str(value.trim(), true) {
+         Ok(orm) => FrameworkFilter::Orm(orm),
+         Err(_) => FrameworkFilter::Framework(Framwork::from(value)),
+      }
+   }
+
+}
+
+impl fmt::Display for FrameworkFilter {
+
+   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      match self {
+         FrameworkFilter::Orm(orm) => write!(f, "{:?}", orm),
+         FrameworkFilter::Framework(framework) => write!(f, "{}", framework),
+      }
+   }
+
+}
+
+impl From<FrameworkFilter> for String {
+
+   fn from(filter: FrameworkFilter) -> Self {
+      filter.to_string()
+   }
+
+}
+
+impl JsonSchema for FrameworkFilter {
+
+   fn schema_name() -> String {
+      "FrameworkFilter".to_string()
+   }
+
+   fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+      String::json_schema(gen)
+   }
+
+}
+
+/**
+ * 规则严重程度枚举，按从低到高的顺序声明，可直接比较大小
+ */
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+
+   Info,
+   Low,
+   #[default]
+   Medium,
+   High,
+   Critical
+
+}
+
+impl Severity {
+
+   // 从高到低排列的全部严重程度，用于统计输出
+   pub const DESCENDING: [Severity; 5] = [Severity::Critical, Severity::High, Severity::Medium, Severity::Low, Severity::Info];
+
+   /**
+    * @descript 严重程度的小写名称
+    */
+   pub fn as_str(&self) -> &'static str {
+      match self {
+         Severity::Info => "info",
+         Severity::Low => "low",
+         Severity::Medium => "medium",
+         Severity::High => "high",
+         Severity::Critical => "critical",
+      }
+   }
+
+}
+
+/**
+ * 源码区域枚举，由词法分析区分代码、注释和字符串字面量
+ */
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanKind {
+
+   Code,
+   Comment,
+   String
+
+}
+
+/**
+ * 注释中命中规则的处理方式
+ */
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentHits {
+
+   // 直接跳过
+   #[default]
+   Skip,
+   // 保留并降为info
+   Downgrade
+
+}
+
+/**
+ * 扫描阶段，用于记录文件在哪个阶段处理失败
+ */
+#[derive(Debug, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanStage {
+
+   // 依赖收集
+   Dependencies,
+   // 接口收集
+   Interfaces,
+   // 规则扫描
+   Rules,
+   // ORM框架分析
+   Orm
+
+}
+
+impl ScanStage {
+
+   /**
+    * @descript 阶段在报告中展示的名称
+    */
+   pub fn as_str(&self) -> &'static str {
+      match self {
+         ScanStage::Dependencies => "依赖收集",
+         ScanStage::Interfaces => "接口收集",
+         ScanStage::Rules => "规则扫描",
+         ScanStage::Orm => "ORM分析",
+      }
+   }
+}
+
+/**
+ * 规则命中结果可信度枚举
+ */
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+
+   Low,
+   #[default]
+   Medium,
+   High
+
+}
+
+impl Confidence {
+
+   /**
+    * @descript 可信度的小写名称
+    */
+   pub fn as_str(&self) -> &'static str {
+      match self {
+         Confidence::Low => "low",
+         Confidence::Medium => "medium",
+         Confidence::High => "high",
+      }
+   }
+
+}
+
+/**
+ * Mybatis中${}所在的SQL上下文，不同的上下文有不同的严重程度和修复方式
+ */
+#[derive(Debug, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlContext {
+
+   // ORDER BY/GROUP BY的列名或排序方向
+   OrderBy,
+   // LIKE模糊查询的条件
+   Like,
+   // IN后面的列表
+   In,
+   // 表名或列名
+   Identifier,
+   // 引号中的字符串
+   Quoted,
+   // 其他位置的条件值
+   Value
+
+}
+
+impl SqlContext {
+
+   /**
+    * @descript 上下文在报告中展示的名称
+    */
+   pub fn as_str(&self) -> &'static str {
+      match self {
+         SqlContext::OrderBy => "排序/分组字段",
+         SqlContext::Like => "LIKE模糊查询",
+         SqlContext::In => "IN列表",
+         SqlContext::Identifier => "表名/列名",
+         SqlContext::Quoted => "引号中的字符串",
+         SqlContext::Value => "条件值",
+      }
+   }
+
+   /**
+    * @descript 上下文对应的严重程度，可以直接改为#{}的条件值风险最高，只能通过白名单修复的表名、列名和排序字段利用受限
+    */
+   pub fn severity(&self) -> Severity {
+      match self {
+         SqlContext::Value => Severity::Critical,
+         SqlContext::Quoted | SqlContext::Like | SqlContext::In => Severity::High,
+         SqlContext::OrderBy | SqlContext::Identifier => Severity::Medium,
+      }
+   }
+
+   /**
+    * @descript 针对上下文的修复建议
+    * @param param ${}引用的参数名
+    */
+   pub fn remediation(&self, param: &str) -> String {
+      match self {
+         SqlContext::OrderBy => format!("排序和分组字段无法使用#{{}}绑定，应在代码中通过白名单将{}映射为固定的列名和ASC/DESC", param),
+         SqlContext::Like => format!("改为LIKE CONCAT('%', #{{{}}}, '%')，或通过<bind>拼接通配符后使用#{{}}引用", param),
+         SqlContext::In => format!("改为<foreach collection=\"{}\" item=\"item\" open=\"(\" separator=\",\" close=\")\">#{{item}}</foreach>逐个绑定", param),
+         SqlContext::Identifier => format!("表名和列名无法使用#{{}}绑定，应在代码中通过白名单校验{}或映射为固定的名称", param),
+         SqlContext::Quoted => format!("去掉两侧的引号并改为#{{{}}}", param),
+         SqlContext::Value => format!("改为#{{{}}}绑定参数", param),
+      }
+   }
+
+}
+
+/**
+ * Spring接口参数的绑定方式
+ */
+#[derive(Debug, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamBinding {
+
+   // @RequestParam，查询参数或表单参数
+   RequestParam,
+   // @PathVariable，路径中的变量
+   PathVariable,
+   // @RequestBody，请求体
+   RequestBody,
+   // @RequestHeader，请求头
+   RequestHeader,
+   // @CookieValue，cookie
+   CookieValue,
+   // 没有注解，简单类型按请求参数绑定，其他类型按属性绑定
+   Implicit
+
+}
+
+impl ParamBinding {
+
+   /**
+    * @descript 根据参数上的注解名获取绑定方式
+    * @return 不是绑定参数的注解时为空
+    */
+   pub fn from_annotation(name: &str) -> Option<Self> {
+      match name {
+         "RequestParam" => Some(ParamBinding::RequestParam),
+         "PathVariable" => Some(ParamBinding::PathVariable),
+         "RequestBody" => Some(ParamBinding::RequestBody),
+         "RequestHeader" => Some(ParamBinding::RequestHeader),
+         "CookieValue" => Some(ParamBinding::CookieValue),
+         _ => None,
+      }
+   }
+
+   /**
+    * @descript 绑定方式对应的注解，没有注解时为空
+    */
+   pub fn as_str(&self) -> &'static str {
+      match self {
+         ParamBinding::RequestParam => "@RequestParam",
+         ParamBinding::PathVariable => "@PathVariable",
+         ParamBinding::RequestBody => "@RequestBody",
+         ParamBinding::RequestHeader => "@RequestHeader",
+         ParamBinding::CookieValue => "@CookieValue",
+         ParamBinding::Implicit => "",
+      }
+   }
+
+}
@@ -0,0 +1,69 @@
+use std::{error::Error, fmt, io};
+
+/**
+ * @Enum ScanError 扫描过程中的错误
+ * @Enum Config 配置文件不存在、格式有误或配置项的取值不合法
+ * @Enum Rule 规则文件读取失败、格式有误或规则无法编译
+ * @Enum Io 文件读写失败
+ * @Enum Parse 文件内容无法解析
+ * @Enum Internal 扫描单个文件时出现的内部错误
+ */
+#[derive(Debug)]
+pub enum ScanError {
+    Config(String),
+    Rule(String),
+    Io { path: String, source: io::Error },
+    Parse { path: String, message: String },
+    Internal(String),
+}
+
+impl ScanError {
+
+    /**
+     * @descript 构建文件读写错误
+     * @param path 读写失败的文件
+     * @param source 原始错误
+     */
+    pub fn io(path: &str, source: io::Error) -> Self {
+        ScanError::Io { path: path.to_string(), source }
+    }
+
+    /**
+     * @descript 构建文件解析错误
+     * @param path 解析失败的文件
+     * @param message 错误信息
+     */
+    pub fn parse(path: &str, message: impl fmt::Display) -> Self {
+        ScanError::Parse { path: path.to_string(), message: message.to_string() }
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::Config(message) => write!(f, "[-]{}", message),
+            ScanError::Rule(message) => write!(f, "[-]{}", message),
+            ScanError::Io { path, source } => write!(f, "[-]文件{}读写失败: {}", path, source),
+            ScanError::Parse { path, message } => write!(f, "[-]文件{}解析失败: {}", path, message),
+            ScanError::Internal(message) => write!(f, "[-]扫描出现内部错误: {}", message),
+        }
+    }
+}
+
+impl Error for ScanError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScanError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<Box<dyn Error>> for ScanError {
+    fn from(err: Box<dyn Error>) -> Self {
+        match err.downcast::<ScanError>() {
+            Ok(err) => *err,
+            Err(err) => ScanError::Internal(err.to_string()),
+        }
+    }
+}
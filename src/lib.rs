@@ -0,0 +1,15 @@
+//! 代码审计协助工具的扫描库，命令行程序只是其中一个调用方
+//!
+//! 通过[`scan`]或[`Application::start`]执行扫描，得到的[`ScanResult`]包含命中的风险代码、接口和依赖组件等信息，
+//! 各类报告(markdown/SARIF/JSON/HTML)都基于[`ScanResult`]生成，配置中未填写报告路径时不会输出对应的报告。
+
+pub mod model;
+pub mod util;
+pub mod enums;
+pub mod project;
+pub mod report;
+pub mod error;
+
+pub use error::ScanError;
+pub use model::{config::Config, rule::Rule, result::ScanResult};
+pub use project::{scan, Application, Scanner, ScannerRegistry, GOProject, JAVAProject, PHPProject};
@@ -0,0 +1,307 @@
+use std::{collections::HashMap, error::Error, fs::{self, File}, io::Write, time::{SystemTime, UNIX_EPOCH}};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{error::ScanError, util::PathUtil};
+
+use super::finding::Finding;
+
+// 基线文件结构有不兼容的改动时需要升级该版本号
+const BASELINE_VERSION: u32 = 1;
+
+/**
+ * @Struct Baseline 已审计过的风险代码基线，用于后续扫描时只关注新增的风险代码
+ * @Field version 基线文件结构的版本号
+ * @Field project_name 生成基线的项目名
+ * @Field generated_at 基线生成时间，unix时间戳(秒)
+ * @Field findings 基线中的风险代码
+ */
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Baseline {
+
+    pub version: u32,
+
+    pub project_name: String,
+
+    pub generated_at: u64,
+
+    pub findings: Vec<BaselineEntry>,
+
+}
+
+/**
+ * @Struct BaselineEntry 基线中的一处风险代码
+ * @Field fingerprint 风险代码指纹，由规则id、相对路径和归一化后的代码计算，与行号无关
+ * @Field rule_id 命中的规则id
+ * @Field file_path 相对于work_dir的文件路径
+ * @Field line 生成基线时所在的行号，仅用于展示
+ * @Field code 命中行去除首尾空白后的代码
+ */
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct BaselineEntry {
+
+    pub fingerprint: String,
+
+    pub rule_id: String,
+
+    pub file_path: String,
+
+    pub line: usize,
+
+    pub code: String,
+
+}
+
+/**
+ * @Enum BaselineState 风险代码与基线对比的状态
+ * @Enum New 基线中不存在的新增风险代码
+ * @Enum Unchanged 基线中已存在的风险代码
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BaselineState {
+    New,
+    Unchanged,
+}
+
+/**
+ * @Struct BaselineDiff 本次扫描与基线的对比结果
+ * @Field baseline_file 对比使用的基线文件
+ * @Field new 新增的风险代码数量
+ * @Field unchanged 与基线一致的风险代码数量
+ * @Field fixed 基线中存在但本次扫描未命中的风险代码
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone, Default)]
+pub struct BaselineDiff {
+
+    pub baseline_file: String,
+
+    pub new: usize,
+
+    pub unchanged: usize,
+
+    pub fixed: Vec<BaselineEntry>,
+
+}
+
+impl Baseline {
+
+    /**
+     * @descript 读取基线文件
+     * @param path 基线文件路径
+     */
+    pub fn load(path: &str) -> Result<Self, ScanError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => return Err(ScanError::Config(format!("基线文件{}读取失败: {}", path, err))),
+        };
+        let baseline = match serde_json::from_str::<Self>(&content) {
+            Ok(baseline) => baseline,
+            Err(err) => return Err(ScanError::Config(format!("基线文件{}格式有错误: {}", path, err))),
+        };
+        if baseline.version != BASELINE_VERSION {
+            return Err(ScanError::Config(format!("基线文件{}的版本{}不受支持,请重新生成基线", path, baseline.version)));
+        }
+        Ok(baseline)
+    }
+
+    /**
+     * @descript 将本次扫描的风险代码保存为基线
+     * @param project_name 项目名
+     * @param findings 已计算过指纹的风险代码
+     * @param work_dir 项目根目录，基线中只保存相对路径
+     * @param path 基线文件路径
+     */
+    pub fn save(project_name: &str, findings: &[Finding], work_dir: &str, path: &str) -> Result<(), Box<dyn Error>> {
+        let baseline = Baseline {
+            version: BASELINE_VERSION,
+            project_name: project_name.to_string(),
+            generated_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default(),
+            findings: findings.iter().map(|finding| BaselineEntry {
+                fingerprint: finding.fingerprint.clone(),
+                rule_id: finding.rule_id.clone(),
+                file_path: PathUtil::relative_path(&finding.file_path, work_dir),
+                line: finding.line,
+                code: finding.code.clone(),
+            }).collect(),
+        };
+        let mut file = File::create(path)?;
+        file.write_all(serde_json::to_string_pretty(&baseline)?.as_bytes())?;
+        Ok(())
+    }
+
+    /**
+     * @descript 计算风险代码的指纹，代码中的空白会被归一化，行号变化不影响指纹
+     * @param rule_id 规则id
+     * @param relative_path 相对于work_dir的文件路径
+     * @param code 命中行的代码
+     * @return 十六进制的sha256摘要
+     */
+    pub fn fingerprint(rule_id: &str, relative_path: &str, code: &str) -> String {
+        let normalized_code = code.split_whitespace().collect::<Vec<&str>>().join(" ");
+        let mut hasher = Sha256::new();
+        for part in [rule_id, relative_path, normalized_code.as_str()] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /**
+     * @descript 将风险代码与基线对比，标记每处风险代码的状态并找出已修复的风险代码
+     * 相同指纹的风险代码按数量对比，基线中有1处而本次有2处时其中1处视为新增
+     * @param findings 已计算过指纹的风险代码，对比后会填充baseline_state
+     * @param baseline_file 基线文件路径
+     * @return 对比结果
+     */
+    pub fn diff(&self, findings: &mut [Finding], baseline_file: &str) -> BaselineDiff {
+        let mut remaining: HashMap<&str, usize> = HashMap::new();
+        for entry in &self.findings {
+            *remaining.entry(entry.fingerprint.as_str()).or_default() += 1;
+        }
+        let mut diff = BaselineDiff {
+            baseline_file: baseline_file.to_string(),
+            ..Default::default()
+        };
+        for finding in findings.iter_mut() {
+            match remaining.get_mut(finding.fingerprint.as_str()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    diff.unchanged += 1;
+                    finding.baseline_state = Some(BaselineState::Unchanged);
+                },
+                _ => {
+                    diff.new += 1;
+                    finding.baseline_state = Some(BaselineState::New);
+                },
+            }
+        }
+        // 剩余未被匹配的基线条目即为已修复
+        for entry in &self.findings {
+            if let Some(count) = remaining.get_mut(entry.fingerprint.as_str()) {
+                if *count > 0 {
+                    *count -= 1;
+                    diff.fixed.push(entry.clone());
+                }
+            }
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::{Severity, SpanKind};
+
+    use super::*;
+
+    fn finding(rule_id: &str, file_path: &str, line: usize, code: &str) -> Finding {
+        Finding {
+            rule_id: rule_id.to_string(),
+            severity: Severity::High,
+            file_path: file_path.to_string(),
+            line,
+            end_line: line,
+            code: code.to_string(),
+            note: String::new(),
+            span: SpanKind::Code,
+            fingerprint: Baseline::fingerprint(rule_id, file_path, code),
+            baseline_state: None,
+            suppression: None,
+            context: vec![],
+        }
+    }
+
+    fn baseline(findings: &[Finding]) -> Baseline {
+        Baseline {
+            version: BASELINE_VERSION,
+            findings: findings.iter().map(|finding| BaselineEntry {
+                fingerprint: finding.fingerprint.clone(),
+                rule_id: finding.rule_id.clone(),
+                file_path: finding.file_path.clone(),
+                line: finding.line,
+                code: finding.code.clone(),
+            }).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fingerprint_ignores_whitespace() {
+        let fingerprint = Baseline::fingerprint("java-exec", "src/A.java", "Runtime.getRuntime().exec(cmd);");
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(fingerprint, Baseline::fingerprint("java-exec", "src/A.java", "  Runtime.getRuntime().exec(cmd);\t"));
+        assert_eq!(fingerprint, Baseline::fingerprint("java-exec", "src/A.java", "Runtime.getRuntime().exec(cmd);"));
+        assert_ne!(fingerprint, Baseline::fingerprint("java-exec", "src/B.java", "Runtime.getRuntime().exec(cmd);"));
+        assert_ne!(fingerprint, Baseline::fingerprint("java-other", "src/A.java", "Runtime.getRuntime().exec(cmd);"));
+        assert_ne!(fingerprint, Baseline::fingerprint("java-exec", "src/A.java", "Runtime.getRuntime().exec(args);"));
+        // 各部分之间有分隔符，拼接后相同的内容指纹不同
+        assert_ne!(Baseline::fingerprint("a", "bc", "d"), Baseline::fingerprint("ab", "c", "d"));
+    }
+
+    #[test]
+    fn diff_counts() {
+        let old = [
+            finding("java-exec", "A.java", 10, "exec(cmd);"),
+            finding("java-exec", "A.java", 20, "exec(cmd);"),
+            finding("java-sql", "B.java", 5, "query(sql);"),
+            finding("java-file", "C.java", 8, "new File(path);"),
+        ];
+        let baseline = baseline(&old);
+        // 行号变化不影响对比，相同代码多出的1处视为新增
+        let mut findings = vec![
+            finding("java-exec", "A.java", 12, "exec(cmd);"),
+            finding("java-exec", "A.java", 22, "exec(cmd);"),
+            finding("java-exec", "A.java", 30, "exec(cmd);"),
+            finding("java-sql", "B.java", 6, "query(sql);"),
+            finding("java-xss", "D.java", 1, "write(html);"),
+        ];
+        let diff = baseline.diff(&mut findings, "baseline.json");
+        assert_eq!(diff.baseline_file, "baseline.json");
+        assert_eq!(diff.unchanged, 3);
+        assert_eq!(diff.new, 2);
+        assert_eq!(diff.fixed.len(), 1);
+        assert_eq!(diff.fixed[0].rule_id, "java-file");
+        let states: Vec<Option<BaselineState>> = findings.iter().map(|finding| finding.baseline_state).collect();
+        assert_eq!(states, vec![
+            Some(BaselineState::Unchanged),
+            Some(BaselineState::Unchanged),
+            Some(BaselineState::New),
+            Some(BaselineState::Unchanged),
+            Some(BaselineState::New),
+        ]);
+    }
+
+    #[test]
+    fn diff_fixed_duplicates() {
+        let old = [
+            finding("java-exec", "A.java", 10, "exec(cmd);"),
+            finding("java-exec", "A.java", 20, "exec(cmd);"),
+        ];
+        let mut findings = vec![finding("java-exec", "A.java", 10, "exec(cmd);")];
+        let diff = baseline(&old).diff(&mut findings, "baseline.json");
+        assert_eq!((diff.new, diff.unchanged, diff.fixed.len()), (0, 1, 1));
+
+        let diff = baseline(&[]).diff(&mut findings, "baseline.json");
+        assert_eq!((diff.new, diff.unchanged, diff.fixed.len()), (1, 0, 0));
+    }
+
+    #[test]
+    fn load_version_mismatch() {
+        let path = std::env::temp_dir().join(format!("code_scan_baseline_{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        fs::write(&path, r#"{"version": 0, "project_name": "demo", "generated_at": 0, "findings": []}"#).unwrap();
+        let result = Baseline::load(&path);
+        fs::write(&path, format!(r#"{{"version": {}, "project_name": "demo", "generated_at": 0, "findings": []}}"#, BASELINE_VERSION)).unwrap();
+        let loaded = Baseline::load(&path);
+        fs::remove_file(&path).unwrap();
+        match result {
+            Err(ScanError::Config(message)) => assert!(message.contains("版本0不受支持"), "{}", message),
+            other => panic!("版本不一致时应返回配置错误: {:?}", other),
+        }
+        assert_eq!(loaded.unwrap().project_name, "demo");
+        assert!(matches!(Baseline::load("/nonexistent/baseline.json"), Err(ScanError::Config(_))));
+    }
+}
@@ -0,0 +1,48 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/**
+ * @Enum CompositeCondition 规则的组合条件，规则关键字命中后再根据组合条件判断是否报告
+ * @Enum All 全部子条件成立
+ * @Enum Any 任一子条件成立
+ * @Enum Not 子条件不成立
+ * @Enum FileContains 命中所在的文件包含指定内容
+ * @Enum FileNotContains 命中所在的文件不包含指定内容
+ * @Enum WithinLines 命中位置前后指定行数内包含指定内容
+ */
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompositeCondition {
+    All(Vec<CompositeCondition>),
+    Any(Vec<CompositeCondition>),
+    Not(Box<CompositeCondition>),
+    FileContains(Pattern),
+    FileNotContains(Pattern),
+    WithinLines(Proximity),
+}
+
+/**
+ * @Enum Pattern 组合条件中要查找的内容
+ * @Enum Literal 直接填写字符串时按包含匹配
+ * @Enum Regex 填写{"regex": "..."}时按正则匹配
+ */
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Pattern {
+    Literal(String),
+    Regex { regex: String },
+}
+
+/**
+ * @Struct Proximity 邻近条件
+ * @Field lines 命中位置前后的行数
+ * @Field pattern 要查找的内容
+ */
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct Proximity {
+
+    pub lines: usize,
+
+    pub pattern: Pattern,
+
+}
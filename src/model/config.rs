@@ -1,24 +1,41 @@
-use std::{error::Error, fs, path::Path};
+use std::{fs, path::{Path, PathBuf}};
 
-use serde::Deserialize;
+use clap::ValueEnum;
+use serde::{de::DeserializeOwned, Deserialize};
 
-use crate::enums::{LangType, Framwork, ORM};
+use crate::{enums::{CommentHits, FrameworkFilter, LangType, Framwork, ORM, Severity}, error::ScanError};
 
-use super::{rule::Rule, unauthorized::Unauthorized};
+use super::{baseline::Baseline, rule::{Rule, RuleSource}, unauthorized::Unauthorized};
+
+// 支持的规则文件后缀，同名时按该顺序优先
+const RULE_FILE_EXTS: [&str; 3] = ["json", "yaml", "yml"];
 
 /**
  * @Sturuct Config 配置类加载
  * @Field lang_type 要扫描的语言类型
  * @Field work_dir 要扫描的目录
- * @Field scan_ext 要扫描的文件后缀
+ * @Field scan_ext 要扫描的文件后缀，为空时使用扫描器的默认后缀
  * @Field exclute_path 不扫描的目录
  * @Field collection_interface 是否尝试收集接口信息
  * @Field framework 使用的框架名
  * @Field orm 使用了什么ORM框架
  * @Field depency_file 第三方依赖配置文件
  * @Feild unauthorized 是否要进行未授权扫描
- * @Field rule 加载的扫描规则 
+ * @Field report_file_path markdown报告的生成路径，为空时不生成
+ * @Field sarif_file_path SARIF报告的生成路径，为空时不生成
+ * @Field json_file_path JSON报告的生成路径，为空时不生成
+ * @Field html_file_path HTML报告的生成路径，为空时不生成
+ * @Field context_lines HTML报告中命中行前后展示的源码行数
+ * @Field fail_on 存在不低于该严重程度的风险代码时以非0状态码退出，为空时不检查
+ * @Field comment_hits 规则只在注释中命中时的处理方式，skip为跳过，downgrade为降为info后保留
+ * @Field baseline_file_path 对比使用的基线文件路径，指定后报告中只详细列出新增的风险代码
+ * @Field save_baseline_path 将本次扫描结果保存为基线的路径，为空时不保存
+ * @Field default_rules 是否加载程序自带的规则文件，默认加载
+ * @Field rule_paths 额外加载的规则文件或目录，按顺序加载，目录中的规则文件按文件名排序
+ * @Field disabled_rules 禁用的规则id
+ * @Field rule 配置文件中直接填写的规则，追加在规则文件之后，加载后为最终使用的扫描规则
  * @Field use_ui 默认为不使用
+ * @Field baseline 加载的基线
  */
 #[derive(Deserialize, Debug)]
 pub struct Config{
@@ -31,6 +48,7 @@ pub struct Config{
 
     pub exclude_path: Vec<String>,
 
+    #[serde(default)]
     pub scan_ext: Vec<String>,
 
     pub collection_interface: bool,
@@ -43,67 +61,293 @@ pub struct Config{
 
     pub unauthorized: Unauthorized,
 
+    #[serde(default)]
     pub report_file_path: String,
 
+    #[serde(default)]
+    pub sarif_file_path: Option<String>,
+
+    #[serde(default)]
+    pub json_file_path: Option<String>,
+
+    #[serde(default)]
+    pub html_file_path: Option<String>,
+
+    #[serde(default = "default_context_lines")]
+    pub context_lines: usize,
+
+    #[serde(default)]
+    pub fail_on: Option<Severity>,
+
+    #[serde(default)]
+    pub comment_hits: CommentHits,
+
+    #[serde(default)]
+    pub baseline_file_path: Option<String>,
+
+    #[serde(default)]
+    pub save_baseline_path: Option<String>,
+
     pub use_ui: bool,
 
+    #[serde(default = "default_rules")]
+    pub default_rules: bool,
+
+    #[serde(default)]
+    pub rule_paths: Vec<String>,
+
+    #[serde(default)]
+    pub disabled_rules: Vec<String>,
+
     #[serde(default = "empty_rule")]
     pub rule: Vec<Rule>,
 
+    #[serde(skip)]
+    pub baseline: Option<Baseline>,
+
 }
 
 impl Config{
 
     /**
-     * @descript 从文件读取配置信息
+     * @descript 从文件读取配置信息，根据后缀选择JSON或YAML格式
+     * @param config_path 配置文件路径，为空时依次查找程序同级目录的config.json、config.yaml、config.yml
      * @return 加载后的Config对象
      */
-    pub fn read_config_by_file() -> Result<Self, Box<dyn Error>>{
-        let programer = std::env::current_exe()?;
-        let parent = programer.parent().unwrap();
-        // 读取同级目录的config.json
-        let config_path = parent.join("config.json");
-        if config_path.is_file() {
-            match fs::read_to_string(&config_path) {
-                Ok(json_string) => {
-                    let config = serde_json::from_str::<Self>(&json_string);
-                    if config.is_err() { panic!("[-]配置文件格式有错误,请检查配置文件格式"); }
-                    let mut config = config?;
-                    if !Path::new(&config.report_file_path).is_absolute() {
-                        config.report_file_path = parent.join(config.report_file_path).to_string_lossy().to_string();
+    pub fn read_config_by_file(config_path: Option<&Path>) -> Result<Self, ScanError>{
+        let programer = Self::program_path()?;
+        let parent = programer.parent().unwrap_or(Path::new("."));
+        // 未指定配置文件时读取同级目录的配置文件，报告的相对路径也相对于程序目录
+        let (config_path, base_dir) = match config_path {
+            Some(path) => (path.to_path_buf(), None),
+            None => {
+                let config_path = ["config.json", "config.yaml", "config.yml"].iter()
+                    .map(|name| parent.join(name))
+                    .find(|path| path.is_file())
+                    .unwrap_or_else(|| parent.join("config.json"));
+                (config_path, Some(parent))
+            },
+        };
+        if !config_path.is_file() {
+            return Err(ScanError::Config(format!("配置文件{}不存在", config_path.display())));
+        }
+        let config_string = match fs::read_to_string(&config_path) {
+            Ok(config_string) => config_string,
+            Err(err) => return Err(ScanError::io(&config_path.to_string_lossy(), err)),
+        };
+        let mut config = match Self::parse_by_ext::<Self>(&config_path, &config_string) {
+            Ok(config) => config,
+            Err(err) => return Err(ScanError::Config(format!("配置文件格式有错误,请检查配置文件格式: {}", err))),
+        };
+        if let Some(base_dir) = base_dir {
+            if !config.report_file_path.is_empty() && !Path::new(&config.report_file_path).is_absolute() {
+                config.report_file_path = base_dir.join(config.report_file_path).to_string_lossy().to_string();
+            }
+            for report_path in [&mut config.sarif_file_path, &mut config.json_file_path, &mut config.html_file_path, &mut config.baseline_file_path, &mut config.save_baseline_path].into_iter().flatten() {
+                if !Path::new(report_path.as_str()).is_absolute() {
+                    *report_path = base_dir.join(report_path.as_str()).to_string_lossy().to_string();
+                }
+            }
+            for rule_path in config.rule_paths.iter_mut() {
+                if !Path::new(rule_path.as_str()).is_absolute() {
+                    *rule_path = base_dir.join(rule_path.as_str()).to_string_lossy().to_string();
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /**
+     * @descript 加载扫描规则
+     * 依次加载程序自带的规则文件、rule_paths中的规则文件和配置文件中的规则，id相同的规则由后加载的覆盖，最后去除禁用的规则
+     * @param self Config对象
+     * @param rule_pack 扫描器自带的规则文件，如rules/java，不包含后缀
+     * @param frameworks 扫描器支持的框架，规则中填写的框架需要是内置框架或其中之一
+     */
+    pub fn load_rule(&mut self, rule_pack: &str, frameworks: &[Framwork]) -> Result<(), ScanError>{
+        let sources = self.read_rule_sources(rule_pack, frameworks)?;
+        self.merge_rules(sources);
+        Ok(())
+    }
+
+    /**
+     * @descript 读取各个来源中的规则，不做去重和覆盖，配置文件中的规则会从rule中取出
+     * @param rule_pack 扫描器自带的规则文件，如rules/java，不包含后缀
+     * @param frameworks 扫描器支持的框架，规则中填写的框架需要是内置框架或其中之一
+     * @return 按加载顺序排列的规则来源
+     */
+    pub fn read_rule_sources(&mut self, rule_pack: &str, frameworks: &[Framwork]) -> Result<Vec<RuleSource>, ScanError> {
+        // 规则来源和生成id使用的前缀
+        let mut sources: Vec<(String, String, Vec<Rule>)> = vec![];
+        if self.default_rules {
+            let rule_file = Self::default_rule_file(rule_pack)?;
+            sources.push((rule_file.to_string_lossy().to_string(), "rule".to_string(), Self::read_rule_file(&rule_file)?));
+        }
+        for rule_path in &self.rule_paths {
+            for rule_file in Self::collect_rule_files(Path::new(rule_path))? {
+                let prefix = rule_file.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+                sources.push((rule_file.to_string_lossy().to_string(), prefix, Self::read_rule_file(&rule_file)?));
+            }
+        }
+        sources.push(("配置文件".to_string(), "config".to_string(), std::mem::take(&mut self.rule)));
+        let sources: Vec<RuleSource> = sources.into_iter()
+            .map(|(name, prefix, mut rules)| {
+                // 未填写id的规则按在所在文件中的位置生成id
+                for (index, rule) in rules.iter_mut().enumerate() {
+                    if rule.id.is_empty() {
+                        rule.id = Rule::default_id(&prefix, index);
                     }
-                    return Ok(config)
+                }
+                RuleSource { name, rules }
+            })
+            .collect();
+        for source in &sources {
+            Self::check_rule_frameworks(source, frameworks)?;
+        }
+        Ok(sources)
+    }
+
+    /**
+     * @descript 检查规则中填写的框架，写错的框架名会让规则永远不生效，加载时直接报错
+     * @param source 规则来源
+     * @param frameworks 扫描器支持的框架
+     */
+    fn check_rule_frameworks(source: &RuleSource, frameworks: &[Framwork]) -> Result<(), ScanError> {
+        let known: Vec<&Framwork> = Framwork::BUILTIN.iter().chain(frameworks).collect();
+        for rule in &source.rules {
+            for filter in &rule.frameworks {
+                let FrameworkFilter::Framework(framework) = filter else {
+                    continue;
+                };
+                if !known.iter().any(|known| known.is(framework)) {
+                    let mut options: Vec<String> = vec![];
+                    for option in known.iter().map(|known| known.to_string())
+                        .chain(ORM::value_variants().iter().filter(|orm| **orm != ORM::None).map(|orm| format!("{:?}", orm))) {
+                        if !options.contains(&option) {
+                            options.push(option);
+                        }
+                    }
+                    return Err(ScanError::Rule(format!("{}中规则[{}]的frameworks中{}不是支持的框架,可选值为: {}", source.name, rule.id, framework, options.join(", "))));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * @descript 合并各个来源中的规则，去除完全相同的规则，id相同的规则由后加载的覆盖，最后去除禁用的规则
+     * @param sources 按加载顺序排列的规则来源
+     */
+    pub fn merge_rules(&mut self, sources: Vec<RuleSource>) {
+        let mut rules: Vec<Rule> = vec![];
+        for source in sources {
+            for rule in Rule::dedup(source.rules) {
+                match rules.iter_mut().find(|kept| kept.id == rule.id) {
+                    Some(kept) => {
+                        println!("[*]规则[{}]已被后加载的同id规则覆盖", rule.id);
+                        *kept = rule;
+                    },
+                    None => rules.push(rule),
+                }
+            }
+        }
+        for id in &self.disabled_rules {
+            match rules.iter().position(|rule| &rule.id == id) {
+                Some(index) => {
+                    rules.remove(index);
                 },
-                Err(_) => panic!("[-]配置文件{}不存在！读取失败", config_path.display()),
-            };
-        } else {
-            panic!("[-]配置文件{}不存在", config_path.display());
+                None => println!("[*]禁用的规则[{}]不存在,已忽略", id),
+            }
         }
+        self.rule = Rule::dedup(rules);
+    }
 
+    /**
+     * @descript 获取程序自带的规则文件，优先读取程序同级目录的规则，不存在时读取当前工作目录的规则
+     * 同一目录下依次查找.json、.yaml、.yml后缀的规则文件
+     * @param rule_name 规则文件相对于程序目录的路径，不包含后缀
+     */
+    fn default_rule_file(rule_name: &str) -> Result<PathBuf, ScanError> {
+        let programer = Self::program_path()?;
+        let parent = programer.parent().unwrap_or(Path::new("."));
+        let current_dir = std::env::current_dir().map_err(|err| ScanError::Config(format!("无法获取当前工作目录: {}", err)))?;
+        for dir in [parent, current_dir.as_path()] {
+            for ext in RULE_FILE_EXTS {
+                let rule_file = dir.join(format!("{}.{}", rule_name, ext));
+                if rule_file.is_file() {
+                    return Ok(rule_file)
+                }
+            }
+        }
+        Ok(current_dir.join(format!("{}.json", rule_name)))
     }
 
     /**
-     * @descript 从配置文件中读取规则加载
-     * @param self Config对象
+     * @descript 获取规则路径下的规则文件，目录只读取第一层的规则文件并按文件名排序，保证加载顺序固定
+     * @param rule_path 规则文件或目录
      */
-    pub fn load_rule(&mut self) -> Result<(), Box<dyn Error>>{
-        let programer = std::env::current_exe()?;
-        let parent = programer.parent().unwrap();
-        let rule_file = match self.lang_type {
-            LangType::JAVA => "rules/java.json",
-            LangType::PHP => "rules/php.json",
-            LangType::GO => "rules/go.json",
-        };
-        let rule_file = parent.join(rule_file);
+    fn collect_rule_files(rule_path: &Path) -> Result<Vec<PathBuf>, ScanError> {
+        if rule_path.is_file() {
+            return Ok(vec![rule_path.to_path_buf()])
+        }
+        if !rule_path.is_dir() {
+            return Err(ScanError::Rule(format!("规则路径{}不存在", rule_path.display())));
+        }
+        let mut rule_files: Vec<PathBuf> = fs::read_dir(rule_path).map_err(|err| ScanError::io(&rule_path.to_string_lossy(), err))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| RULE_FILE_EXTS.iter().any(|rule_ext| ext == *rule_ext)))
+            .collect();
+        rule_files.sort();
+        Ok(rule_files)
+    }
+
+    /**
+     * @descript 读取一个规则文件
+     * @param rule_file 规则文件路径
+     */
+    fn read_rule_file(rule_file: &Path) -> Result<Vec<Rule>, ScanError> {
         println!("[*]开始读取规则文件：{:?}", rule_file);
-        match fs::read_to_string(&rule_file) {
-            Ok(rule_json) => {
-                let rule = serde_json::from_str::<Vec<Rule>>(&rule_json);
-                if rule.is_err() { panic!("[-]请检查规则文件格式"); }
-                self.rule = rule?
-            },
-            Err(_) => panic!("[-]规则文件{}不存在！读取失败", rule_file.display()),
+        let rule_string = match fs::read_to_string(rule_file) {
+            Ok(rule_string) => rule_string,
+            Err(_) => return Err(ScanError::Rule(format!("规则文件{}不存在！读取失败", rule_file.display()))),
         };
+        match Self::parse_by_ext::<Vec<Rule>>(rule_file, &rule_string) {
+            Ok(rule) => Ok(rule),
+            Err(err) => Err(ScanError::Rule(format!("请检查规则文件{}的格式: {}", rule_file.display(), err))),
+        }
+    }
+
+    /**
+     * @descript 根据文件后缀解析配置或规则，.yaml/.yml按YAML解析，其余按JSON解析
+     * @param path 文件路径
+     * @param content 文件内容
+     */
+    fn parse_by_ext<T: DeserializeOwned>(path: &Path, content: &str) -> Result<T, String> {
+        let is_yaml = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+        if is_yaml {
+            // serde_yaml的枚举需要写成!tag形式，先转换为JSON值再解析，使YAML与JSON的写法保持一致
+            let value = serde_yaml::from_str::<serde_json::Value>(content).map_err(|err| err.to_string())?;
+            serde_json::from_value::<T>(value).map_err(|err| err.to_string())
+        } else {
+            serde_json::from_str::<T>(content).map_err(|err| err.to_string())
+        }
+    }
+
+    // 程序自身的路径，默认的配置文件和规则文件都相对于程序所在目录
+    fn program_path() -> Result<PathBuf, ScanError> {
+        std::env::current_exe().map_err(|err| ScanError::Config(format!("无法获取程序所在目录: {}", err)))
+    }
+
+    /**
+     * @descript 配置了基线文件时加载基线
+     * @param self Config对象
+     */
+    pub fn load_baseline(&mut self) -> Result<(), ScanError> {
+        if let Some(baseline_file_path) = &self.baseline_file_path {
+            let baseline = Baseline::load(baseline_file_path)?;
+            println!("[*]基线加载完毕，共计风险代码：{}处", baseline.findings.len());
+            self.baseline = Some(baseline);
+        }
         Ok(())
     }
 
@@ -112,4 +356,14 @@ impl Config{
 // 初始化规则
 pub fn empty_rule() -> Vec<Rule>{
     vec![]
+}
+
+// 默认加载程序自带的规则
+pub fn default_rules() -> bool {
+    true
+}
+
+// 默认的上下文行数
+pub fn default_context_lines() -> usize {
+    3
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/**
+ * @Struct Dependency 依赖文件中声明的第三方组件
+ * @Field group_id 组织id
+ * @Field artifact_id 组件id
+ * @Field version 版本号，已尝试从<properties>中替换占位符
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct Dependency {
+
+    pub group_id: String,
+
+    pub artifact_id: String,
+
+    pub version: Option<String>,
+
+}
@@ -0,0 +1,80 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::enums::ParamBinding;
+
+/**
+ * @Struct Endpoint Spring Controller中的处理方法对应的接口，类和方法上有多个路径时每个组合为一个接口
+ * @Field file_path Controller所在的文件路径
+ * @Field line 处理方法声明的行号
+ * @Field http_methods 允许的请求方法，没有限制请求方法时为空
+ * @Field path 类和方法上的路径拼接后的完整路径
+ * @Field controller Controller的全限定名
+ * @Field method 处理方法名
+ * @Field params 处理方法中从请求绑定的参数
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct Endpoint {
+
+    pub file_path: String,
+
+    pub line: usize,
+
+    pub http_methods: Vec<String>,
+
+    pub path: String,
+
+    pub controller: String,
+
+    pub method: String,
+
+    pub params: Vec<EndpointParam>,
+
+}
+
+/**
+ * @Struct EndpointParam 处理方法的参数
+ * @Field name 请求中的参数名，注解中没有指定时与变量名相同
+ * @Field variable 方法参数的变量名
+ * @Field type_name 参数类型
+ * @Field binding 参数的绑定方式
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct EndpointParam {
+
+    pub name: String,
+
+    pub variable: String,
+
+    pub type_name: String,
+
+    pub binding: ParamBinding,
+
+}
+
+impl Endpoint {
+
+    /**
+     * @descript 接口的简要描述，格式为`请求方法 路径(处理方法)`，没有限制请求方法时为ALL
+     */
+    pub fn route(&self) -> String {
+        let http_methods = match self.http_methods.is_empty() {
+            true => String::from("ALL"),
+            false => self.http_methods.join(","),
+        };
+        format!("{} {}({}.{})", http_methods, self.path, self.controller, self.method)
+    }
+}
+
+impl EndpointParam {
+
+    /**
+     * @descript 参数的声明，如@RequestParam("id") Long id
+     */
+    pub fn declaration(&self) -> String {
+        match self.binding {
+            ParamBinding::Implicit | ParamBinding::RequestBody => format!("{} {} {}", self.binding.as_str(), self.type_name, self.variable).trim_start().to_string(),
+            _ => format!("{}(\"{}\") {} {}", self.binding.as_str(), self.name, self.type_name, self.variable),
+        }
+    }
+}
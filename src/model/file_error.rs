@@ -0,0 +1,54 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{enums::ScanStage, error::ScanError};
+
+/**
+ * @Struct FileError 扫描过程中处理失败的文件，失败的文件会被跳过，不影响其他文件的扫描，也用于记录不影响扫描结果的警告
+ * @Field file_path 处理失败的文件路径
+ * @Field stage 处理失败或出现警告的扫描阶段
+ * @Field message 失败原因或警告信息
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct FileError {
+
+    pub file_path: String,
+
+    pub stage: ScanStage,
+
+    pub message: String,
+
+}
+
+impl FileError {
+
+    /**
+     * @descript 根据扫描错误构建失败记录，同时在控制台输出
+     * @param stage 处理失败的扫描阶段
+     * @param file_path 处理失败的文件路径
+     * @param error 扫描错误
+     */
+    pub fn new(stage: ScanStage, file_path: &str, error: &ScanError) -> Self {
+        println!("{}", error);
+        FileError {
+            file_path: file_path.to_string(),
+            stage,
+            message: error.to_string().trim_start_matches("[-]").to_string(),
+        }
+    }
+
+    /**
+     * @descript 构建不影响扫描结果的警告记录，同时在控制台输出
+     * @param stage 出现警告的扫描阶段
+     * @param file_path 出现警告的文件路径
+     * @param message 警告信息
+     */
+    pub fn warning(stage: ScanStage, file_path: &str, message: &str) -> Self {
+        println!("[*]文件{}: {}", file_path, message);
+        FileError {
+            file_path: file_path.to_string(),
+            stage,
+            message: message.to_string(),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::enums::{Severity, SpanKind, SqlContext};
+
+use super::baseline::BaselineState;
+
+/**
+ * @Struct Finding 规则命中的风险代码
+ * @Field rule_id 命中的规则id
+ * @Field severity 命中规则的严重程度
+ * @Field file_path 命中的文件路径
+ * @Field line 命中的行号，跨行命中时为开始的行号，从1开始
+ * @Field end_line 命中结束的行号，逐行匹配时与line相同
+ * @Field code 命中行去除首尾空白后的代码，跨行命中时为各行以换行符拼接
+ * @Field note 命中规则的说明
+ * @Field span 命中位置所在的源码区域
+ * @Field fingerprint 与行号无关的指纹，用于和基线对比
+ * @Field baseline_state 与基线对比的状态，未指定基线时为空
+ * @Field suppression 通过源码注释忽略时记录的忽略信息
+ * @Field context 命中行前后的源码，仅用于HTML报告
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct Finding {
+
+    pub rule_id: String,
+
+    pub severity: Severity,
+
+    pub file_path: String,
+
+    pub line: usize,
+
+    pub end_line: usize,
+
+    pub code: String,
+
+    pub note: String,
+
+    pub span: SpanKind,
+
+    pub fingerprint: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_state: Option<BaselineState>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
+
+    #[serde(skip)]
+    pub context: Vec<SourceLine>,
+
+}
+
+/**
+ * @Struct Suppression 源码注释中的忽略信息
+ * @Field file_level 是否为code_scan:ignore-file文件级忽略
+ * @Field line 忽略标记所在的行号
+ * @Field reason 忽略的原因
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct Suppression {
+
+    pub file_level: bool,
+
+    pub line: usize,
+
+    pub reason: String,
+
+}
+
+/**
+ * @Struct SourceLine 带行号的一行源码
+ * @Field line 行号，从1开始
+ * @Field code 该行的原始内容
+ */
+#[derive(Serialize, Debug, Clone)]
+pub struct SourceLine {
+
+    pub line: usize,
+
+    pub code: String,
+
+}
+
+/**
+ * @Struct OrmFinding ORM框架中发现的SQL注入风险点
+ * @Field file_path 命中的文件路径
+ * @Field line 命中的行号，从1开始
+ * @Field code 命中行的内容
+ * @Field namespace mybatis中为mapper的namespace，java代码中为所在类的全限定名
+ * @Field statement_id mybatis中为语句的id，java代码中为所在的方法名，hbm.xml中为命名查询名
+ * @Field note 风险点说明
+ * @Field severity 严重程度，${}按所在的SQL上下文区分，拼接SQL的风险点为high
+ * @Field context ${}所在的SQL上下文，其他风险点为空
+ * @Field remediation 针对SQL上下文的修复建议，其他风险点为空
+ * @Field param ${}中引用的参数名，其他风险点为空
+ * @Field mapper_method 语句对应的mapper接口方法，找不到时为空
+ * @Field routes 直接或间接调用该语句的接口，格式为`请求方法 路径(处理方法)`
+ */
+#[derive(Serialize, JsonSchema, Debug, Default, Clone)]
+pub struct OrmFinding {
+
+    pub file_path: String,
+
+    pub line: usize,
+
+    pub code: String,
+
+    pub namespace: String,
+
+    pub statement_id: String,
+
+    pub note: String,
+
+    pub severity: Severity,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<SqlContext>,
+
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub remediation: String,
+
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub param: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapper_method: Option<MapperMethod>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<String>,
+
+}
+
+/**
+ * @Struct MapperMethod mapper接口中与语句id同名的方法
+ * @Field file_path mapper接口的文件路径
+ * @Field line 方法声明的行号
+ * @Field signature 方法签名
+ * @Field parameter 与${}引用的参数对应的方法参数，找不到时为空
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct MapperMethod {
+
+    pub file_path: String,
+
+    pub line: usize,
+
+    pub signature: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<MapperParameter>,
+
+}
+
+/**
+ * @Struct MapperParameter mapper方法的参数
+ * @Field name 参数名
+ * @Field param @Param指定的名称，没有@Param时为空
+ * @Field type_name 参数类型
+ */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct MapperParameter {
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param: Option<String>,
+
+    pub type_name: String,
+
+}
+
+impl OrmFinding {
+
+    /**
+     * @descript 获取风险点所在语句的完整id，namespace和statement_id以.连接
+     */
+    pub fn statement(&self) -> String {
+        [self.namespace.as_str(), self.statement_id.as_str()].iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(".")
+    }
+}
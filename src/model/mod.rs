@@ -1,6 +1,17 @@
 pub mod config;
 pub mod rule;
 pub mod unauthorized;
+pub mod finding;
+pub mod result;
+pub mod dependency;
+pub mod baseline;
+pub mod condition;
+pub mod file_error;
+pub mod web_xml;
+pub mod endpoint;
+
+use schemars::JsonSchema;
+use serde::Serialize;
 
 /**
  * 未授权结构体
@@ -8,6 +19,7 @@ pub mod unauthorized;
  * @Field body 响应体
  * @Field url 对应的url
  */
+#[derive(Serialize, JsonSchema, Debug, Clone)]
 pub struct UnauthorizedInterface {
     pub code: u16,
     pub body: String,
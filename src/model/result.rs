@@ -0,0 +1,54 @@
+use super::{baseline::BaselineDiff, endpoint::Endpoint, file_error::FileError, finding::{Finding, OrmFinding}, dependency::Dependency, web_xml::WebXml, Interfaces, UnauthorizedInterface};
+
+/**
+ * @Struct ScanResult 扫描过程中收集到的结构化结果，供各类报告使用
+ * @Field files 根据后缀收集到的文件绝对路径
+ * @Field interfaces 收集到的接口列表
+ * @Field endpoints Spring接口的请求方法、路径、处理方法和参数，未使用Spring时为空
+ * @Field web_xml web.xml中的servlet和过滤器信息，未找到web.xml时为空
+ * @Field dependencies 依赖文件中的组件
+ * @Field findings 根据规则命中的风险代码
+ * @Field baseline 与基线的对比结果，未指定基线时为空
+ * @Field suppressed 通过源码注释忽略的风险代码，不参与基线对比和fail_on检查
+ * @Field mybatis_findings mybatis中${}形式的SQL注入风险点
+ * @Field hibernate_findings hibernate/JPA中拼接查询语句的SQL注入风险点
+ * @Field unauthorized 验证出的未授权接口
+ * @Field total_line 扫描的总行数
+ * @Field not_null_line 扫描的非空行数
+ * @Field errors 扫描过程中处理失败的文件
+ * @Field warnings 扫描过程中不影响结果完整性的问题，如跳过的非utf8编码的行，对应的文件仍然被扫描
+ */
+#[derive(Debug, Default, Clone)]
+pub struct ScanResult {
+
+    pub files: Vec<String>,
+
+    pub interfaces: Interfaces,
+
+    pub endpoints: Vec<Endpoint>,
+
+    pub web_xml: Option<WebXml>,
+
+    pub dependencies: Vec<Dependency>,
+
+    pub findings: Vec<Finding>,
+
+    pub baseline: Option<BaselineDiff>,
+
+    pub suppressed: Vec<Finding>,
+
+    pub mybatis_findings: Vec<OrmFinding>,
+
+    pub hibernate_findings: Vec<OrmFinding>,
+
+    pub unauthorized: Vec<UnauthorizedInterface>,
+
+    pub total_line: usize,
+
+    pub not_null_line: usize,
+
+    pub errors: Vec<FileError>,
+
+    pub warnings: Vec<FileError>,
+
+}
@@ -1,20 +1,187 @@
 use serde::Deserialize;
 
-use crate::enums::RuleCondition;
+use crate::enums::{Confidence, FrameworkFilter, Framwork, RuleCondition, RuleScope, Severity, SpanKind, ORM};
+
+use super::condition::CompositeCondition;
 
 /**
  * @Struct Rule 检测规则
+ * @Field id 规则id，为空时加载规则后按规则位置生成
  * @Field keyword 匹配关键字
  * @Field note 备注
  * @Field condition 匹配规则：0-包含，1-正则
+ * @Field severity 严重程度，默认为medium
+ * @Field confidence 命中结果的可信度，默认为medium
+ * @Field cwe 对应的CWE编号，如CWE-78
+ * @Field owasp 对应的OWASP Top 10分类，如A03:2021
+ * @Field tags 规则标签
+ * @Field references 参考链接
+ * @Field match_in 规则生效的源码区域，默认只匹配代码，如密钥类规则可以只匹配字符串
+ * @Field scope 匹配范围，默认逐行匹配，file和window(n)可以匹配跨行的代码
+ * @Field when 组合条件，关键字命中后还需要满足该条件才会报告
+ * @Field include_ext 规则只检查这些后缀的文件，为空时检查全部文件
+ * @Field include_paths 规则只检查匹配这些glob的文件，路径相对于work_dir，为空时检查全部文件
+ * @Field exclude_paths 规则不检查匹配这些glob的文件，路径相对于work_dir
+ * @Field frameworks 规则只在使用了这些框架的项目中生效，为空时对全部项目生效
+ * @Field examples 规则示例，rules lint时作为规则的测试用例执行
  */
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Rule{
 
+    #[serde(default)]
+    pub id: String,
+
     pub keyword: String,
 
     pub note: String,
 
-    pub condition: RuleCondition
+    pub condition: RuleCondition,
+
+    #[serde(default)]
+    pub severity: Severity,
+
+    #[serde(default)]
+    pub confidence: Confidence,
+
+    #[serde(default)]
+    pub cwe: Option<String>,
+
+    #[serde(default)]
+    pub owasp: Option<String>,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub references: Vec<String>,
+
+    #[serde(default = "default_match_in")]
+    pub match_in: Vec<SpanKind>,
+
+    #[serde(default)]
+    pub scope: RuleScope,
+
+    #[serde(default)]
+    pub when: Option<CompositeCondition>,
+
+    #[serde(default)]
+    pub include_ext: Vec<String>,
+
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+
+    #[serde(default)]
+    pub exclude_paths: Vec<String>,
+
+    #[serde(default)]
+    pub frameworks: Vec<FrameworkFilter>,
+
+    #[serde(default)]
+    pub examples: RuleExamples,
+
+}
+
+/**
+ * @Struct RuleExamples 规则示例
+ * @Field positive 应该命中规则的源码片段
+ * @Field negative 不应该命中规则的源码片段
+ */
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct RuleExamples {
+
+    #[serde(default)]
+    pub positive: Vec<String>,
+
+    #[serde(default)]
+    pub negative: Vec<String>,
+
+}
+
+/**
+ * @Struct RuleSource 一个规则来源中按原顺序排列的规则，去重和覆盖之前的状态，用于规则检查定位问题
+ * @Field name 规则来源，规则文件的路径或配置文件
+ * @Field rules 规则列表，未填写的id已按位置生成
+ */
+#[derive(Debug, Clone)]
+pub struct RuleSource {
+
+    pub name: String,
+
+    pub rules: Vec<Rule>,
+
+}
+
+// 规则默认只匹配代码区域
+fn default_match_in() -> Vec<SpanKind> {
+    vec![SpanKind::Code]
+}
+
+impl Rule {
+
+    /**
+     * @descript 生成默认的规则id，规则在所在规则文件中的位置从1开始编号
+     * @param prefix id前缀，程序自带的规则为rule，其他规则文件为文件名，配置文件中的规则为config
+     * @param index 规则在所在规则文件中的下标
+     * @return 规则id
+     */
+    pub fn default_id(prefix: &str, index: usize) -> String {
+        format!("{}-{:03}", prefix, index + 1)
+    }
+
+    /**
+     * @descript 去除完全相同的规则，相同规则只保留第一条，避免同一处代码重复报告
+     * @param rules 规则列表
+     * @return 去重后的规则列表
+     */
+    pub fn dedup(rules: Vec<Rule>) -> Vec<Rule> {
+        let mut unique: Vec<Rule> = Vec::with_capacity(rules.len());
+        for rule in rules {
+            match unique.iter().find(|kept| kept.is_same_as(&rule)) {
+                Some(kept) => println!("[*]规则[{}]与规则[{}]完全相同,已忽略", rule.id, kept.id),
+                None => unique.push(rule),
+            }
+        }
+        unique
+    }
+
+    /**
+     * @descript 判断两条规则的匹配方式、关键字、说明以及所有生效范围的配置是否完全相同
+     */
+    fn is_same_as(&self, other: &Rule) -> bool {
+        self.condition == other.condition && self.keyword == other.keyword && self.note == other.note && self.match_in == other.match_in && self.scope == other.scope && self.when == other.when
+            && self.include_ext == other.include_ext && self.include_paths == other.include_paths
+            && self.exclude_paths == other.exclude_paths && self.frameworks == other.frameworks
+    }
+
+    /**
+     * @descript 判断规则是否适用于项目使用的框架
+     * 开发框架和ORM框架分别判断，规则只填写了其中一类时另一类不做限制
+     * @param framework 项目使用的开发框架
+     * @param orm 项目使用的ORM框架
+     */
+    pub fn applies_to_project(&self, framework: &Framwork, orm: &ORM) -> bool {
+        let frameworks: Vec<&Framwork> = self.frameworks.iter().filter_map(|filter| match filter {
+            FrameworkFilter::Framework(framework) => Some(framework),
+            FrameworkFilter::Orm(_) => None,
+        }).collect();
+        let orms: Vec<&ORM> = self.frameworks.iter().filter_map(|filter| match filter {
+            FrameworkFilter::Orm(orm) => Some(orm),
+            FrameworkFilter::Framework(_) => None,
+        }).collect();
+        (frameworks.is_empty() || frameworks.iter().any(|candidate| candidate.is(framework))) && (orms.is_empty() || orms.contains(&orm))
+    }
+
+    /**
+     * @descript 获取CWE编号对应的说明页面
+     * @return CWE页面地址，未配置或格式不正确时为空
+     */
+    pub fn cwe_url(&self) -> Option<String> {
+        let cwe = self.cwe.as_ref()?;
+        let number = cwe.trim().trim_start_matches("CWE-").trim_start_matches("cwe-");
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+            return None
+        }
+        Some(format!("https://cwe.mitre.org/data/definitions/{}.html", number))
+    }
 
-}
\ No newline at end of file
+}
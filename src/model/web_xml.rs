@@ -0,0 +1,50 @@
+/**
+ * @Struct WebXml web.xml中的关键信息
+ * @Field file_path web.xml的路径
+ * @Field servlets servlet与url的映射
+ * @Field filters 过滤器与url的映射
+ */
+#[derive(Debug, Default, Clone)]
+pub struct WebXml {
+
+    pub file_path: String,
+
+    pub servlets: Vec<ServletMapping>,
+
+    pub filters: Vec<FilterMapping>,
+
+}
+
+/**
+ * @Struct ServletMapping servlet-mapping的映射关系
+ * @Field names servlet名
+ * @Field classes servlet名对应的处理类
+ * @Field urls 映射的url
+ */
+#[derive(Debug, Default, Clone)]
+pub struct ServletMapping {
+
+    pub names: Vec<String>,
+
+    pub classes: Vec<String>,
+
+    pub urls: Vec<String>,
+
+}
+
+/**
+ * @Struct FilterMapping 过滤器的映射关系
+ * @Field name 过滤器名
+ * @Field class 过滤器的全路径类名
+ * @Field url 过滤器匹配的url
+ */
+#[derive(Debug, Default, Clone)]
+pub struct FilterMapping {
+
+    pub name: String,
+
+    pub class: String,
+
+    pub url: String,
+
+}
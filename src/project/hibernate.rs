@@ -0,0 +1,220 @@
+use std::error::Error;
+
+use regex::Regex;
+
+use crate::{enums::{ScanStage, Severity}, model::{file_error::FileError, finding::OrmFinding}, util::{java_source::JavaSource, FileUtil}};
+
+use super::JAVAProject;
+
+impl JAVAProject {
+
+    /**
+     * @descript 分析Hibernate/JPA中的SQL注入：拼接查询语句的createQuery等调用、拼接或内联SpEL的原生@Query以及hbm.xml中文本替换的命名查询
+     * @param root_dir 项目根目录
+     * @param exclude_path 排除的目录
+     * @param errors 处理失败的文件
+     * @return Vec<OrmFinding> 命中的风险点
+     */
+    pub fn analyze_hibernate_sql_injection(root_dir: &str, exclude_path: &Vec<String>, errors: &mut Vec<FileError>) -> Result<Vec<OrmFinding>, Box<dyn Error>> {
+        let java_sources = Self::load_java_sources(root_dir, exclude_path, errors)?;
+        let mut hibernate_findings = Self::hibernate_query_findings(&java_sources)?;
+        // 关联调用的接口
+        Self::trace_orm_findings(&java_sources, &mut hibernate_findings);
+        hibernate_findings.append(&mut Self::analyze_hbm_named_query(root_dir, exclude_path, errors)?);
+        Ok(hibernate_findings)
+    }
+
+    /**
+     * @descript 分析java源码中拼接查询语句的createQuery等调用以及拼接或内联SpEL的原生@Query
+     * @param java_sources 项目中的java源码
+     * @return Vec<OrmFinding> 命中的风险点
+     */
+    pub fn hibernate_query_findings(java_sources: &[(String, JavaSource)]) -> Result<Vec<OrmFinding>, Box<dyn Error>> {
+        let mut hibernate_findings: Vec<OrmFinding> = vec![];
+        // 执行查询语句的方法，sqlRestriction为Criteria中直接写入的SQL片段
+        let query_call = Regex::new(r"\.\s*(createQuery|createSQLQuery|createNativeQuery|sqlRestriction)\s*\(")?;
+        let query_annotation = Regex::new(r"@Query\s*\(")?;
+        let native_query = Regex::new(r"nativeQuery\s*=\s*true")?;
+        // 除了:#{}和?#{}绑定的参数，SpEL表达式的结果会直接替换到SQL中
+        let inline_spel = Regex::new(r"(^|[^:?])#\{")?;
+        for (java_file, source) in java_sources {
+            if !query_call.is_match(&source.source) && !source.source.contains("@Query") {
+                continue;
+            }
+            let mut push = |offset: usize, statement_id: Option<String>, note: String| {
+                let line = source.line_of(offset);
+                hibernate_findings.push(OrmFinding {
+                    file_path: java_file.clone(),
+                    line,
+                    code: source.line_text(line),
+                    namespace: source.type_name_at(offset),
+                    statement_id: statement_id.unwrap_or_default(),
+                    note,
+                    severity: Severity::High,
+                    ..OrmFinding::default()
+                });
+            };
+
+            for capture in query_call.captures_iter(&source.code) {
+                let open = capture.get(0).map(|call| call.end() - 1).unwrap_or_default();
+                let Some((start, end)) = source.arguments(open).into_iter().next() else {
+                    continue;
+                };
+                // 参数是变量时在同一个方法中查找该变量的拼接过程
+                let dynamic_parts = source.spliced_parts(start, end);
+                if !dynamic_parts.is_empty() {
+                    let offset = capture.get(0).map(|call| call.start()).unwrap_or_default();
+                    push(offset, source.method_at(offset), format!("{}的查询语句拼接了[{}]", &capture[1], dynamic_parts.join(", ")));
+                }
+            }
+
+            for annotation in query_annotation.find_iter(&source.code) {
+                let open = annotation.end() - 1;
+                let Some(close) = source.matching_close(open) else {
+                    continue;
+                };
+                if !native_query.is_match(&source.code[open..close]) {
+                    continue;
+                }
+                // value可以省略属性名
+                let Some((start, end)) = source.arguments(open).into_iter()
+                    .find(|(start, end)| {
+                        let argument = &source.code[*start..*end];
+                        argument.starts_with("value") || !argument.contains('=')
+                    }) else {
+                    continue;
+                };
+                let start = match source.code[start..end].starts_with("value") {
+                    true => source.code[start..end].find('=').map(|index| start + index + 1).unwrap_or(start),
+                    false => start,
+                };
+                let statement_id = source.method_after(annotation.start()).map(|method| method.name);
+                let dynamic_parts = source.dynamic_parts(start, end);
+                if !dynamic_parts.is_empty() {
+                    push(annotation.start(), statement_id, format!("原生@Query的SQL拼接了[{}]", dynamic_parts.join(", ")));
+                } else if inline_spel.is_match(&source.literal_text(start, end)) {
+                    push(annotation.start(), statement_id, String::from("原生@Query的SQL中使用#{}表达式直接替换"));
+                }
+            }
+        }
+        Ok(hibernate_findings)
+    }
+
+    /**
+     * @descript 分析hbm.xml中的命名查询，查询语句中的${}或#{}会在解析前做文本替换，风险点的statement_id为命名查询名
+     * @param root_dir 项目根目录
+     * @param exclude_path 排除的目录
+     * @param errors 处理失败的文件
+     */
+    fn analyze_hbm_named_query(root_dir: &str, exclude_path: &Vec<String>, errors: &mut Vec<FileError>) -> Result<Vec<OrmFinding>, Box<dyn Error>> {
+        let mut hbm_findings = vec![];
+        let named_query = Regex::new(r#"(?s)<\s*(query|sql-query)\b([^>]*)>(.*?)</\s*(query|sql-query)\s*>"#)?;
+        let query_name = Regex::new(r#"name\s*=\s*"([^"]*)""#)?;
+        let placeholder = Regex::new(r"[$#]\{[^}]*\}")?;
+        for hbm_file in FileUtil::collection_file(root_dir, &vec![String::from("hbm.xml")], exclude_path)? {
+            let content = match FileUtil::read_file(&hbm_file) {
+                Ok(content) => content,
+                Err(err) => {
+                    errors.push(FileError::new(ScanStage::Orm, &hbm_file, &err));
+                    continue;
+                },
+            };
+            for capture in named_query.captures_iter(&content) {
+                let (Some(attributes), Some(body)) = (capture.get(2), capture.get(3)) else {
+                    continue;
+                };
+                let name = query_name.captures(attributes.as_str()).map(|name| name[1].to_string()).unwrap_or_default();
+                for matched in placeholder.find_iter(body.as_str()) {
+                    let line = content[..body.start() + matched.start()].matches('\n').count() + 1;
+                    hbm_findings.push(OrmFinding {
+                        file_path: hbm_file.clone(),
+                        line,
+                        code: content.lines().nth(line - 1).unwrap_or_default().trim().to_string(),
+                        namespace: String::new(),
+                        statement_id: name.clone(),
+                        note: format!("命名查询中使用{}直接替换", matched.as_str()),
+                        severity: Severity::High,
+                        ..OrmFinding::default()
+                    });
+                }
+            }
+        }
+        Ok(hbm_findings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn findings(source: &str) -> Vec<(usize, String, String)> {
+        let java_sources = vec![(String::from("UserDao.java"), JavaSource::parse("UserDao.java", source.to_string()))];
+        JAVAProject::hibernate_query_findings(&java_sources).unwrap().into_iter()
+            .map(|finding| (finding.line, finding.statement_id, finding.note))
+            .collect()
+    }
+
+    #[test]
+    fn concat_query_argument() {
+        let dao = r#"package com.demo;
+public class UserDao {
+    public List<User> find(String name) {
+        return session.createQuery("from User where name = '" + name + "'").list();
+    }
+}
+"#;
+        assert_eq!(findings(dao), vec![(4, String::from("find"), String::from("createQuery的查询语句拼接了[name]"))]);
+    }
+
+    #[test]
+    fn string_builder_append_chain() {
+        let dao = r#"package com.demo;
+public class UserDao {
+    public List<User> search(String order) {
+        StringBuilder hql = new StringBuilder("from User u");
+        hql.append(" where u.deleted = 0")
+            .append(" order by ").append(order);
+        return entityManager.createQuery(hql.toString()).getResultList();
+    }
+}
+"#;
+        let result = findings(dao);
+        assert_eq!(result.len(), 1);
+        assert_eq!((result[0].0, result[0].1.as_str()), (7, "search"));
+        assert!(result[0].2.contains("order"), "{}", result[0].2);
+    }
+
+    #[test]
+    fn string_format_argument() {
+        let dao = r#"package com.demo;
+public class UserDao {
+    public List<User> sort(String column) {
+        String sql = String.format("select * from t_user order by %s", column);
+        return session.createSQLQuery(sql).list();
+    }
+}
+"#;
+        let result = findings(dao);
+        assert_eq!(result.len(), 1);
+        assert_eq!((result[0].0, result[0].1.as_str()), (5, "sort"));
+        assert!(result[0].2.starts_with("createSQLQuery") && result[0].2.contains("column"), "{}", result[0].2);
+    }
+
+    #[test]
+    fn constant_query_not_flagged() {
+        let dao = r#"package com.demo;
+public class UserDao {
+    private static final String PREFIX = "from User u ";
+    private static final String ALL = PREFIX + "where u.deleted = 0";
+
+    public List<User> all() {
+        String hql = "from User where id = :id";
+        session.createQuery(hql).setParameter("id", 1);
+        session.createQuery(ALL).list();
+        return session.createQuery("from User " + "order by id").list();
+    }
+}
+"#;
+        assert_eq!(findings(dao), vec![]);
+    }
+}
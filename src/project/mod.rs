@@ -1,27 +1,46 @@
-use std::{error::Error, fs::File, io::Write, time::Instant};
+use std::{error::Error, marker::PhantomData, time::Instant};
 
 use http_req::request;
 use regex::Regex;
 
-use crate::{model::{config::Config, UnauthorizedInterface, Interfaces}, util::rule_check::{TOTAL_LINE, NOT_NULL_LINE}};
+use crate::{enums::{Framwork, LangType}, error::ScanError, model::{baseline::Baseline, config::Config, UnauthorizedInterface, Interfaces, result::ScanResult}, report::{SarifReport, JsonReport, HtmlReport, MarkdownReport}};
 
 pub mod java_project;
+pub mod hibernate;
+pub mod mybatis;
+pub mod orm_trace;
+pub mod spring;
 pub mod php_project;
 pub mod go_project;
+pub mod registry;
 
 pub struct JAVAProject;
 pub struct PHPProject;
 pub struct GOProject;
 
 /**
- * 启动结构体
- * @Field project 实现功能的结构体代码
- * @Field config 配置信息
+ * 扫描器注册表，根据配置中的语言类型选择扫描器
+ * @Field scanners 已注册的扫描器，同一语言以后注册的为准
+ */
+pub struct ScannerRegistry {
+    scanners: Vec<Box<dyn Scanner>>,
+}
+
+/**
+ * 启动结构体，扫描器和配置信息在启动时传入，不需要创建实例
  */
 pub struct Application<T>
     where T: Scanner{
-    pub project: T,
-    pub config: Config
+    scanner: PhantomData<T>
+}
+
+/**
+ * @descript 使用内置的扫描器扫描项目，需要扫描其他语言时通过ScannerRegistry注册扫描器
+ * @param config 配置信息，规则和基线在扫描开始时加载
+ * @return ScanResult 扫描结果，配置了报告路径时同时输出对应的报告
+ */
+pub fn scan(config: Config) -> Result<ScanResult, ScanError> {
+    ScannerRegistry::default().scan(config)
 }
 
 /**
@@ -29,20 +48,25 @@ pub struct Application<T>
  */
 impl<T: Scanner> Application<T> {
 
-    
-    pub fn start(project: T, mut config: Config, report_file: &mut File) -> Result<(), Box<dyn Error>> {
+    /**
+     * @descript 执行扫描任务，报告只是扫描结果的一种输出，report_file_path为空时不生成markdown报告
+     * @param project 实现了Scanner的扫描器
+     * @param config 配置信息
+     * @return ScanResult 扫描结果
+     */
+    pub fn start(project: T, mut config: Config) -> Result<ScanResult, Box<dyn Error>> {
         let start_time = Instant::now();
-        project.init(&mut config, report_file)?;
+        project.init(&mut config)?;
+        let mut scan_result = ScanResult::default();
         // 开始扫描任务，获取接口列表
-        let interface_list = project.start(&mut config, report_file)?;
-        // 扫描行数统计
-        {
-            match report_file.write(format!(
-                "\n  \n# 本次扫描共计: {}行,不为空的行数为: {}行", TOTAL_LINE.lock().unwrap(), NOT_NULL_LINE.lock().unwrap())
-            .as_bytes()) {
-                Ok(_) => println!("[+]行数统计成功"),
-                Err(_) => println!("[-]统计行数失败了!"),
-            }
+        let interface_list = project.start(&mut config, &mut scan_result)?;
+        scan_result.interfaces = interface_list.clone();
+        // 处理失败被跳过的文件和失败的分析阶段
+        if !scan_result.errors.is_empty() {
+            println!("[*]共{}处处理失败,相关文件或分析阶段已跳过", scan_result.errors.len());
+        }
+        if !scan_result.warnings.is_empty() {
+            println!("[*]共{}处警告,相关文件仍已扫描", scan_result.warnings.len());
         }
         // 是否需要对接口进行验证
         if config.unauthorized.valid {
@@ -97,20 +121,39 @@ impl<T: Scanner> Application<T> {
                     },
                 };
             }
-            // 写入报告未授权接口
-            let mut unauthorized_interface_info: String = String::from("### 未授权接口列表:   \n```\n");
-            for interface in &unauthorized_interfaces {
-                unauthorized_interface_info.push_str(format!(" - [Code]:{} [URL]{} \n[Body]{}", interface.code, interface.url, interface.body).as_str())
-            }
-            unauthorized_interface_info.push_str("\n```\n");
-            report_file.write(unauthorized_interface_info.as_bytes())?;
+            scan_result.unauthorized = unauthorized_interfaces;
             println!("未授权接口扫描完毕")
         }
+        // 保存本次扫描结果作为基线
+        if let Some(save_baseline_path) = &config.save_baseline_path {
+            Baseline::save(&config.project_name, &scan_result.findings, &config.work_dir, save_baseline_path)?;
+            println!("[+]基线已保存到: {}", save_baseline_path);
+        }
+        // 输出markdown格式的报告
+        if !config.report_file_path.is_empty() {
+            MarkdownReport::write(&config, &scan_result, &config.report_file_path)?;
+            println!("[+]markdown报告已输出到: {}", config.report_file_path);
+        }
+        // 输出SARIF格式的报告
+        if let Some(sarif_file_path) = &config.sarif_file_path {
+            SarifReport::write(&config, &scan_result, sarif_file_path)?;
+            println!("[+]SARIF报告已输出到: {}", sarif_file_path);
+        }
+        // 输出JSON格式的报告
+        if let Some(json_file_path) = &config.json_file_path {
+            JsonReport::write(&config, &scan_result, json_file_path)?;
+            println!("[+]JSON报告已输出到: {}", json_file_path);
+        }
+        // 输出HTML格式的报告
+        if let Some(html_file_path) = &config.html_file_path {
+            HtmlReport::write(&config, &scan_result, html_file_path)?;
+            println!("[+]HTML报告已输出到: {}", html_file_path);
+        }
         let end_time = Instant::now();
         let duration = end_time - start_time;
         println!("本次项目扫描执行时间: {}", Self::format_duration(duration));
-        
-        Ok(())
+
+        Ok(scan_result)
     }
 
     // 格式化输出时间
@@ -137,27 +180,79 @@ impl<T: Scanner> Application<T> {
 }
 
 /**
- * 功能代码接口
+ * 功能代码接口，实现后注册到ScannerRegistry即可扫描新的语言
+ * @fn lang_type 扫描的语言，配置中的lang_type与之相同时使用该扫描器
+ * @fn default_exts 配置中未填写scan_ext时扫描的文件后缀
+ * @fn default_rule_pack 自带的规则文件，相对于程序目录且不包含后缀，如rules/java
+ * @fn frameworks 支持的开发框架，None默认支持
+ * @fn prepare 检查配置中的框架并补充默认配置
  * @fn init 初始化函数，加载配置
  * @fn start 正式启动扫描任务
  */
 pub trait Scanner {
-    // 默认所有的项目扫描都需要加载初始的配置文件
-    fn init(&self, _config: &mut Config, report_file: &mut File) -> Result<(), Box<dyn Error>>{
-        let config_out: String = format!("# 项目《{}》扫描结果报告:  \n\n### 本次扫描加载的初始配置为:   \n```\n{:#?}\n```  \n\n", _config.project_name, _config);
-        report_file.write(config_out.as_bytes())?;
-        _config.load_rule()?;
-        let mut rule_out: String = String::from("### 加载规则文件列表如下:  \n\n```\n");
-        for item in &_config.rule {
-            rule_out.push_str(format!("- {:?}\n", item).as_str())
+    fn lang_type(&self) -> LangType;
+
+    fn default_exts(&self) -> Vec<String>;
+
+    fn default_rule_pack(&self) -> String;
+
+    fn frameworks(&self) -> Vec<Framwork> {
+        vec![]
+    }
+
+    fn prepare(&self, config: &mut Config) -> Result<(), ScanError> {
+        let frameworks = self.frameworks();
+        if config.framework != Framwork::None && !frameworks.iter().any(|framework| framework.is(&config.framework)) {
+            let supported: Vec<String> = std::iter::once(Framwork::None).chain(frameworks).map(|framework| framework.to_string()).collect();
+            return Err(ScanError::Config(format!("配置中填写的框架{}不是{}支持的框架,可选值为: {}", config.framework, self.lang_type(), supported.join(", "))))
+        }
+        if config.scan_ext.is_empty() {
+            config.scan_ext = self.default_exts();
         }
-        rule_out.push_str("\n```  \n\n");
-        report_file.write(rule_out.as_bytes())?;
+        Ok(())
+    }
+
+    // 默认所有的项目扫描都需要加载初始的配置文件
+    fn init(&self, _config: &mut Config) -> Result<(), Box<dyn Error>>{
+        self.prepare(_config)?;
+        _config.load_rule(&self.default_rule_pack(), &self.frameworks())?;
+        _config.load_baseline()?;
         println!("[+]规则加载完毕，共计使用规则共：{:?}条", _config.rule.len());
         Ok(())
     }
 
-    fn start(&self, _config: &mut Config, _report_file: &mut File) -> Result<Interfaces, Box<dyn Error>>{
+    fn start(&self, _config: &mut Config, _result: &mut ScanResult) -> Result<Interfaces, Box<dyn Error>>{
         Ok(vec![])
     }
+}
+
+// 注册表中的扫描器以引用的形式交给Application执行
+impl<S: Scanner + ?Sized> Scanner for &S {
+    fn lang_type(&self) -> LangType {
+        (**self).lang_type()
+    }
+
+    fn default_exts(&self) -> Vec<String> {
+        (**self).default_exts()
+    }
+
+    fn default_rule_pack(&self) -> String {
+        (**self).default_rule_pack()
+    }
+
+    fn frameworks(&self) -> Vec<Framwork> {
+        (**self).frameworks()
+    }
+
+    fn prepare(&self, config: &mut Config) -> Result<(), ScanError> {
+        (**self).prepare(config)
+    }
+
+    fn init(&self, config: &mut Config) -> Result<(), Box<dyn Error>> {
+        (**self).init(config)
+    }
+
+    fn start(&self, config: &mut Config, result: &mut ScanResult) -> Result<Interfaces, Box<dyn Error>> {
+        (**self).start(config, result)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,522 @@
+use std::error::Error;
+
+use regex::Regex;
+
+use crate::{enums::{ScanStage, Severity, SqlContext}, model::{file_error::FileError, finding::OrmFinding}, util::{java_source::{JavaSource, MethodDeclaration}, FileUtil}};
+
+use super::JAVAProject;
+
+impl JAVAProject {
+
+    /**
+     * @descript 分析Mybatis中的SQL注入：mapper xml和mapper注解中${}形式的SQL、@SelectProvider等SQL构造方法中拼接的SQL以及MyBatis-Plus条件构造器中拼接的SQL片段
+     * @param root_dir 项目根目录
+     * @param exclude_path 排除的目录
+     * @param errors 处理失败的文件
+     * @return Vec<OrmFinding> 命中的风险点
+     */
+    pub fn analyze_mybatis_sql_injection(root_dir: &str, exclude_path: &Vec<String>, errors: &mut Vec<FileError>) -> Result<Vec<OrmFinding>, Box<dyn Error>> {
+        let java_sources = Self::load_java_sources(root_dir, exclude_path, errors)?;
+        let mut mybatis_findings = Self::analyze_mapper_xml(root_dir, exclude_path, errors)?;
+        mybatis_findings.append(&mut Self::analyze_mapper_java(&java_sources)?);
+        // 关联mapper接口方法和调用的接口
+        Self::trace_orm_findings(&java_sources, &mut mybatis_findings);
+        // mapper方法声明为整数类型的参数无法注入
+        mybatis_findings.retain(|finding| !Self::is_integer_param(finding));
+        // SQL构造方法的风险点记录在构造类中，按文件重新排列
+        mybatis_findings.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.line.cmp(&b.line)));
+        Ok(mybatis_findings)
+    }
+
+    /**
+     * @descript 分析mapper xml中${}形式的SQL，记录所在mapper的namespace和语句id
+     */
+    fn analyze_mapper_xml(root_dir: &str, exclude_path: &Vec<String>, errors: &mut Vec<FileError>) -> Result<Vec<OrmFinding>, Box<dyn Error>> {
+        let mut mybatis_findings: Vec<OrmFinding> = vec![];
+        // 定义用于匹配 Mybatis XML 文件的正则表达式模式
+        let mapper = Regex::new(r#"<\s*mapper\b([^>]*)>"#)?;
+        let namespace = Regex::new(r#"namespace\s*=\s*"([^"]*)""#)?;
+        let statement = Regex::new(r#"(?s)<\s*(select|insert|update|delete|sql)\b([^>]*)>(.*?)</\s*(select|insert|update|delete|sql)\s*>"#)?;
+        let statement_id = Regex::new(r#"\bid\s*=\s*"([^"]*)""#)?;
+        let comment = Regex::new(r#"(?s)<!--.*?-->"#)?;
+        // 找到${}注入形式的字符串
+        let placeholder = Regex::new(r#"\$\{(.*?)\}"#)?;
+        let context_matcher = SqlContextMatcher::new()?;
+        let xml_files = FileUtil::collection_file(root_dir, &vec![String::from("xml")], exclude_path)?;
+        for xml_file in xml_files {
+            // 读取xml文件，读取失败记录后跳过该文件
+            let xml_content = match FileUtil::read_file(&xml_file) {
+                Ok(xml_content) => xml_content,
+                Err(err) => {
+                    errors.push(FileError::new(ScanStage::Orm, &xml_file, &err));
+                    continue;
+                },
+            };
+            // 判断文件内容是否匹配 Mybatis XML 文件的模式
+            let Some(mapper_attributes) = mapper.captures(&xml_content).and_then(|capture| capture.get(1)) else {
+                continue;
+            };
+            let mapper_namespace = namespace.captures(mapper_attributes.as_str()).map(|capture| capture[1].to_string()).unwrap_or_default();
+            let comments: Vec<(usize, usize)> = comment.find_iter(&xml_content).map(|matched| (matched.start(), matched.end())).collect();
+            // 每个语句的范围和id
+            let statements: Vec<(usize, usize, String)> = statement.captures_iter(&xml_content)
+                .filter_map(|capture| {
+                    let matched = capture.get(0)?;
+                    let id = statement_id.captures(&capture[2]).map(|id| id[1].to_string()).unwrap_or_default();
+                    Some((matched.start(), matched.end(), id))
+                })
+                .collect();
+            for capture in placeholder.captures_iter(&xml_content) {
+                let Some(matched) = capture.get(0) else {
+                    continue;
+                };
+                if comments.iter().any(|(start, end)| *start <= matched.start() && matched.start() < *end) {
+                    continue;
+                }
+                // <if test>、<bind value>等属性是OGNL表达式，其中的${}不会替换到SQL中
+                if Self::in_xml_tag(&xml_content, matched.start()) {
+                    continue;
+                }
+                // 行号从1开始
+                let line = xml_content[..matched.start()].matches('\n').count() + 1;
+                let (statement_start, statement_end, id) = statements.iter()
+                    .find(|(start, end, _)| *start <= matched.start() && matched.start() < *end)
+                    .cloned()
+                    .unwrap_or((matched.start(), matched.end(), String::new()));
+                let base = OrmFinding {
+                    file_path: xml_file.clone(),
+                    line,
+                    code: xml_content.lines().nth(line - 1).unwrap_or_default().trim().to_string(),
+                    namespace: mapper_namespace.clone(),
+                    statement_id: id,
+                    ..OrmFinding::default()
+                };
+                mybatis_findings.push(context_matcher.classify(base, &xml_content[statement_start..matched.start()], &xml_content[matched.end()..statement_end], matched.as_str(), &capture[1], "在"));
+            }
+        }
+        Ok(mybatis_findings)
+    }
+
+    /**
+     * @descript 分析java代码中的mapper注解、SQL构造方法和MyBatis-Plus条件构造器
+     * @param java_sources 项目中的java源码
+     */
+    fn analyze_mapper_java(java_sources: &[(String, JavaSource)]) -> Result<Vec<OrmFinding>, Box<dyn Error>> {
+        let mut mybatis_findings: Vec<OrmFinding> = vec![];
+        let sql_annotation = Regex::new(r"@(Select|Update|Delete|Insert)\s*\(")?;
+        let provider_annotation = Regex::new(r"@(Select|Update|Delete|Insert)Provider\s*\(")?;
+        let provider_type = Regex::new(r"^(type\s*=\s*|value\s*=\s*)?([\w.]+)\s*\.\s*class$")?;
+        // 条件构造器中直接拼接到SQL中的方法，apply和having之后的参数是绑定的参数
+        let wrapper_call = Regex::new(r"\.\s*(apply|having|last|inSql|notInSql|exists|notExists|orderBy|orderByAsc|orderByDesc|groupBy)\s*\(")?;
+        let placeholder = Regex::new(r"\$\{([^}]*)\}")?;
+        let context_matcher = SqlContextMatcher::new()?;
+        for (java_file, source) in java_sources {
+            if !source.source.contains("${") && !source.source.contains("Provider") && !source.source.contains("Wrapper") {
+                continue;
+            }
+            let finding = |offset: usize, namespace: String, statement_id: Option<String>, note: String| {
+                let line = source.line_of(offset);
+                OrmFinding {
+                    file_path: java_file.clone(),
+                    line,
+                    code: source.line_text(line),
+                    namespace,
+                    statement_id: statement_id.unwrap_or_default(),
+                    note,
+                    severity: Severity::High,
+                    ..OrmFinding::default()
+                }
+            };
+
+            // @Select等注解中的${}
+            for annotation in sql_annotation.captures_iter(&source.code) {
+                let Some(matched) = annotation.get(0) else {
+                    continue;
+                };
+                let Some(close) = source.matching_close(matched.end() - 1) else {
+                    continue;
+                };
+                let statement_id = source.method_after(matched.start()).map(|method| method.name);
+                // 注解中的SQL由字符串拼接而成，按字符串的内容判断上下文
+                let sql = source.literal_text(matched.end(), close);
+                for placeholder in placeholder.captures_iter(&source.source[matched.end()..close]) {
+                    let Some(matched_placeholder) = placeholder.get(0) else {
+                        continue;
+                    };
+                    let offset = matched.end() + matched_placeholder.start();
+                    // 只检查字符串中的${}
+                    if !source.code[offset..].starts_with(' ') {
+                        continue;
+                    }
+                    let before = source.literal_text(matched.end(), offset);
+                    // <script>中标签属性里的${}不会替换到SQL中
+                    if Self::in_xml_tag(&before, before.len()) {
+                        continue;
+                    }
+                    let after = sql.get(before.len() + matched_placeholder.len()..).unwrap_or_default();
+                    let location = format!("@{}的", &annotation[1]);
+                    let base = finding(offset, source.type_name_at(offset), statement_id.clone(), String::new());
+                    mybatis_findings.push(context_matcher.classify(base, &before, after, matched_placeholder.as_str(), &placeholder[1], &location));
+                }
+            }
+
+            // @SelectProvider等注解指定的SQL构造方法
+            for annotation in provider_annotation.captures_iter(&source.code) {
+                let Some(matched) = annotation.get(0) else {
+                    continue;
+                };
+                let open = matched.end() - 1;
+                let statement_id = source.method_after(matched.start()).map(|method| method.name);
+                let arguments = source.arguments(open);
+                let Some(type_name) = arguments.iter()
+                    .find_map(|(start, end)| provider_type.captures(&source.code[*start..*end]).map(|capture| capture[2].to_string())) else {
+                    continue;
+                };
+                // 未指定method时使用与mapper方法同名的构造方法
+                let method_name = arguments.iter()
+                    .find(|(start, end)| source.code[*start..*end].starts_with("method"))
+                    .map(|(start, end)| source.literal_text(*start, *end))
+                    .or(statement_id.clone())
+                    .unwrap_or_default();
+                let type_name = type_name.rsplit('.').next().unwrap_or_default().to_string();
+                // SQL构造类可以是mapper中的内部类，优先在mapper所在文件中查找
+                let provider = source.method_body(&type_name, &method_name)
+                    .map(|method| (java_file, source, method))
+                    .or_else(|| java_sources.iter().find_map(|(path, other)| other.method_body(&type_name, &method_name).map(|method| (path, other, method))));
+                let Some((provider_file, provider_source, MethodDeclaration { name_offset, body: Some((body_open, body_close)), .. })) = provider else {
+                    continue;
+                };
+                let mut dynamic_parts = provider_source.method_spliced_parts(body_open);
+                dynamic_parts.extend(placeholder.find_iter(&provider_source.source[body_open..body_close]).map(|matched| matched.as_str().to_string()));
+                if dynamic_parts.is_empty() {
+                    continue;
+                }
+                let line = provider_source.line_of(name_offset);
+                mybatis_findings.push(OrmFinding {
+                    file_path: provider_file.clone(),
+                    line,
+                    code: provider_source.line_text(line),
+                    namespace: source.type_name_at(matched.start()),
+                    statement_id: statement_id.unwrap_or_default(),
+                    note: format!("@{}Provider的SQL构造方法{}.{}拼接了[{}]", &annotation[1], type_name, method_name, dynamic_parts.join(", ")),
+                    severity: Severity::High,
+                    ..OrmFinding::default()
+                });
+            }
+
+            // MyBatis-Plus条件构造器中拼接的SQL片段
+            if source.source.contains("Wrapper") {
+                for call in wrapper_call.captures_iter(&source.code) {
+                    let Some(matched) = call.get(0) else {
+                        continue;
+                    };
+                    let arguments = source.arguments(matched.end() - 1);
+                    let sql_arguments = match &call[1] {
+                        "apply" | "having" => &arguments[..arguments.len().min(1)],
+                        _ => &arguments[..],
+                    };
+                    let dynamic_parts: Vec<String> = sql_arguments.iter()
+                        .flat_map(|(start, end)| source.spliced_parts(*start, *end))
+                        .collect();
+                    if !dynamic_parts.is_empty() {
+                        mybatis_findings.push(finding(matched.start(), source.type_name_at(matched.start()), source.method_at(matched.start()), format!("MyBatis-Plus条件构造器的{}拼接了[{}]", &call[1], dynamic_parts.join(", "))));
+                    }
+                }
+            }
+        }
+        Ok(mybatis_findings)
+    }
+
+    /**
+     * @descript 获取${}中引用的参数名，去掉jdbcType等属性
+     * @param placeholder ${}中的内容
+     */
+    fn placeholder_param(placeholder: &str) -> String {
+        placeholder.split(',').next().unwrap_or_default().trim().to_string()
+    }
+
+    /**
+     * @descript 判断位置是否在xml标签内，如<if test="...">的属性中
+     * @param content xml内容
+     * @param offset 要判断的位置
+     */
+    fn in_xml_tag(content: &str, offset: usize) -> bool {
+        let before = &content[..offset];
+        match (before.rfind('<'), before.rfind('>')) {
+            // CDATA和注释以<!开头，比较运算符<后面是空白或数字
+            (Some(open), close) if close.is_none_or(|close| close < open) => before[open + 1..].starts_with(|char: char| char.is_ascii_alphabetic() || char == '/'),
+            _ => false,
+        }
+    }
+
+    /**
+     * @descript 判断${}引用的是否为mapper方法中声明为整数类型的参数，整数参数替换到SQL中不会造成注入
+     */
+    fn is_integer_param(finding: &OrmFinding) -> bool {
+        if finding.context.is_none() || finding.param.contains(['.', '[']) {
+            return false;
+        }
+        finding.mapper_method.as_ref()
+            .and_then(|mapper_method| mapper_method.parameter.as_ref())
+            .is_some_and(|parameter| matches!(parameter.type_name.as_str(), "int" | "long" | "short" | "byte" | "Integer" | "Long" | "Short" | "Byte"))
+    }
+}
+
+/**
+ * @Struct SqlContextMatcher 根据${}前后的SQL判断所在的上下文
+ * @Field tag xml标签和CDATA的边界
+ * @Field bind <bind>标签
+ * @Field like 前面是LIKE和通配符
+ * @Field in_list 前面是IN (
+ * @Field order_by ORDER BY/GROUP BY及其后面的内容
+ * @Field clause 结束排序和分组字段的关键字
+ * @Field identifier 前面是FROM、JOIN等后面跟表名或列名的关键字
+ * @Field column_before 前面是WHERE、AND等条件的开始
+ * @Field column_after 后面是比较运算符
+ */
+struct SqlContextMatcher {
+    tag: Regex,
+    bind: Regex,
+    like: Regex,
+    in_list: Regex,
+    order_by: Regex,
+    clause: Regex,
+    identifier: Regex,
+    column_before: Regex,
+    column_after: Regex,
+}
+
+impl SqlContextMatcher {
+
+    fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(SqlContextMatcher {
+            tag: Regex::new(r"</?[A-Za-z][^>]*>|<!\[CDATA\[|\]\]>")?,
+            // <script>注解中的引号会被转义
+            bind: Regex::new(r#"<\s*bind\s+name\s*=\s*\\?"([^"\\]*)\\?"\s+value\s*=\s*\\?"([^"\\]*)\\?""#)?,
+            like: Regex::new(r"\blike\s*(concat\s*\()?[\s'%,|+]*$")?,
+            in_list: Regex::new(r"\bin\s*\(\s*$")?,
+            order_by: Regex::new(r".*\b(order|group)\s+by\b(.*)$")?,
+            clause: Regex::new(r"\b(select|from|where|and|or|limit|offset|having|union)\b")?,
+            identifier: Regex::new(r"(\b(from|join|into|update|table|truncate|select|distinct)|\.)\s*$")?,
+            column_before: Regex::new(r"(\b(where|and|or|set|on)|[,(])\s*$")?,
+            column_after: Regex::new(r"^\s*(=|<|>|!=|\.|\b(like|in|is|between|not|from)\b)")?,
+        })
+    }
+
+    /**
+     * @descript 判断${}的SQL上下文，补充风险点的说明、严重程度和修复建议
+     * @param finding 已经填写位置的风险点
+     * @param before 所在语句中${}前面的内容
+     * @param after 所在语句中${}后面的内容
+     * @param placeholder ${}
+     * @param content ${}中的内容
+     * @param location 说明的前缀
+     */
+    fn classify(&self, finding: OrmFinding, before: &str, after: &str, placeholder: &str, content: &str, location: &str) -> OrmFinding {
+        let context = self.context(&self.sql_text(before), &self.sql_text(after));
+        let mut param = JAVAProject::placeholder_param(content);
+        let mut note = format!("{}{}中使用{}直接拼接SQL", location, context.as_str(), placeholder);
+        // <bind>定义的变量，参数为表达式中引用的变量
+        if let Some(bound) = self.bind.captures_iter(before).filter(|bind| bind[1] == param).last().and_then(|bind| Self::bind_param(&bind[2])) {
+            note.push_str(&format!("，{}由<bind>引用{}", param, bound));
+            param = bound;
+        }
+        OrmFinding {
+            note,
+            severity: context.severity(),
+            context: Some(context),
+            remediation: context.remediation(&param),
+            param,
+            ..finding
+        }
+    }
+
+    /**
+     * @descript 去掉xml标签，转为小写并合并空白，便于匹配关键字
+     */
+    fn sql_text(&self, fragment: &str) -> String {
+        self.tag.replace_all(fragment, " ").to_lowercase().split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    /**
+     * @descript 根据${}前后的SQL判断上下文
+     * @param before ${}前面的SQL，已经转为小写
+     * @param after ${}后面的SQL，已经转为小写
+     */
+    fn context(&self, before: &str, after: &str) -> SqlContext {
+        let before = before.trim_end();
+        if self.like.is_match(before) {
+            return SqlContext::Like;
+        }
+        // 前面的单引号为奇数个时在字符串中
+        if before.matches('\'').count() % 2 == 1 {
+            return SqlContext::Quoted;
+        }
+        if self.in_list.is_match(before) {
+            return SqlContext::In;
+        }
+        if self.order_by.captures(before).is_some_and(|order_by| !self.clause.is_match(&order_by[2])) {
+            return SqlContext::OrderBy;
+        }
+        if self.identifier.is_match(before) || after.starts_with('.') || (self.column_before.is_match(before) && self.column_after.is_match(after)) {
+            return SqlContext::Identifier;
+        }
+        SqlContext::Value
+    }
+
+    /**
+     * @descript 获取<bind>的value表达式中引用的第一个变量，如'%' + name + '%'中的name
+     */
+    fn bind_param(value: &str) -> Option<String> {
+        let mut in_string = false;
+        let mut start = None;
+        for (index, char) in value.char_indices().chain(std::iter::once((value.len(), ' '))) {
+            match char {
+                '\'' => in_string = !in_string,
+                _ if in_string => {},
+                _ if char.is_alphanumeric() || char == '_' || (char == '.' && start.is_some()) => {
+                    if start.is_none() && !char.is_ascii_digit() {
+                        start = Some(index);
+                    }
+                },
+                _ => if let Some(start) = start.take() {
+                    // 去掉方法调用，如name.trim()
+                    let variable = match value[index..].trim_start().starts_with('(') {
+                        true => value[start..index].rsplit_once('.').map(|(variable, _)| variable).unwrap_or_default(),
+                        false => &value[start..index],
+                    };
+                    if !variable.is_empty() && !matches!(variable, "null" | "true" | "false") {
+                        return Some(variable.to_string());
+                    }
+                },
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::finding::{MapperMethod, MapperParameter};
+
+    use super::*;
+
+    fn classify(before: &str, after: &str, content: &str) -> OrmFinding {
+        let matcher = SqlContextMatcher::new().unwrap();
+        matcher.classify(OrmFinding::default(), before, after, &format!("${{{}}}", content), content, "在")
+    }
+
+    #[test]
+    fn sql_contexts() {
+        let cases = [
+            ("select * from user where name like '%", "%'", SqlContext::Like),
+            ("select * from user where name LIKE concat('%', ", ", '%')", SqlContext::Like),
+            ("select * from user where name like ", "", SqlContext::Like),
+            ("select * from user where name = '", "'", SqlContext::Quoted),
+            ("select * from user where id in (", ")", SqlContext::In),
+            ("<where> id IN (<if test=\"ids != null\">", "</if>)</where>", SqlContext::In),
+            ("select * from user order by ", "", SqlContext::OrderBy),
+            ("select * from user ORDER BY id ", "", SqlContext::OrderBy),
+            ("select * from user group by name, ", "", SqlContext::OrderBy),
+            ("select * from user order by id limit ", "", SqlContext::Value),
+            ("select * from ", " where id = 1", SqlContext::Identifier),
+            ("select * from user u join ", " r on u.id = r.uid", SqlContext::Identifier),
+            ("select * from user where ", " = 1", SqlContext::Identifier),
+            ("select * from user where u.", " = 1", SqlContext::Identifier),
+            ("select ", ".name from user", SqlContext::Identifier),
+            ("select * from user where id = ", "", SqlContext::Value),
+            ("update user set name = ", " where id = 1", SqlContext::Value),
+        ];
+        for (before, after, context) in cases {
+            let finding = classify(before, after, "param");
+            assert_eq!(finding.context, Some(context), "{} ${{param}} {}", before, after);
+            assert_eq!(finding.severity, context.severity());
+            assert_eq!(finding.param, "param");
+            assert_eq!(finding.remediation, context.remediation("param"));
+        }
+    }
+
+    #[test]
+    fn placeholder_attributes() {
+        let finding = classify("select * from user where id = ", "", "id, jdbcType=VARCHAR");
+        assert_eq!(finding.param, "id");
+        assert_eq!(finding.note, "在条件值中使用${id, jdbcType=VARCHAR}直接拼接SQL");
+    }
+
+    #[test]
+    fn xml_tag_attributes() {
+        let cases = [
+            ("<if test=\"${name} != null\">", true),
+            ("<bind name=\"p\" value=\"'%' + ${name}\" />", true),
+            ("</if> and name = ${name}", false),
+            ("where age < ${age}", false),
+            ("where age <${age}", false),
+            ("<![CDATA[ where age < ${age} ]]>", false),
+            ("<!-- ${name} -->", false),
+        ];
+        for (content, in_tag) in cases {
+            let offset = content.find("${").unwrap();
+            assert_eq!(JAVAProject::in_xml_tag(content, offset), in_tag, "{}", content);
+        }
+    }
+
+    #[test]
+    fn bind_params() {
+        let cases = [
+            ("'%' + name + '%'", Some("name")),
+            ("name.trim()", Some("name")),
+            ("'%' + user.name + '%'", Some("user.name")),
+            ("_parameter.keyword", Some("_parameter.keyword")),
+            ("'abc' + 'd'", None),
+            ("null", None),
+            ("1", None),
+        ];
+        for (value, param) in cases {
+            assert_eq!(SqlContextMatcher::bind_param(value).as_deref(), param, "{}", value);
+        }
+    }
+
+    #[test]
+    fn bind_variable() {
+        let before = "<bind name=\"pattern\" value=\"'%' + name.trim() + '%'\" /> select * from user where name like ";
+        let finding = classify(before, "", "pattern");
+        assert_eq!(finding.context, Some(SqlContext::Like));
+        assert_eq!(finding.param, "name");
+        assert!(finding.note.ends_with("，pattern由<bind>引用name"));
+
+        // <script>注解中的引号被转义
+        let before = r#"<script><bind name=\"pattern\" value=\"'%' + keyword + '%'\"/> select * from user where name = "#;
+        assert_eq!(classify(before, "", "pattern").param, "keyword");
+
+        // 只使用同名的<bind>
+        let before = "<bind name=\"other\" value=\"name\" /> select * from user where name = ";
+        assert_eq!(classify(before, "", "pattern").param, "pattern");
+    }
+
+    #[test]
+    fn integer_params() {
+        let finding = |param: &str, type_name: &str| {
+            let mut finding = classify("select * from user limit ", "", param);
+            finding.mapper_method = Some(MapperMethod {
+                file_path: "UserMapper.java".to_string(),
+                line: 1,
+                signature: String::new(),
+                parameter: Some(MapperParameter { name: param.to_string(), param: None, type_name: type_name.to_string() }),
+            });
+            finding
+        };
+        assert!(JAVAProject::is_integer_param(&finding("limit", "int")));
+        assert!(JAVAProject::is_integer_param(&finding("limit", "Long")));
+        assert!(!JAVAProject::is_integer_param(&finding("limit", "String")));
+        assert!(!JAVAProject::is_integer_param(&finding("page.limit", "Integer")));
+        assert!(!JAVAProject::is_integer_param(&finding("ids[0]", "Integer")));
+
+        let mut without_method = finding("limit", "int");
+        without_method.mapper_method = None;
+        assert!(!JAVAProject::is_integer_param(&without_method));
+
+        let mut without_context = finding("limit", "int");
+        without_context.context = None;
+        assert!(!JAVAProject::is_integer_param(&without_context));
+    }
+}
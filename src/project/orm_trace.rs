@@ -0,0 +1,223 @@
+use std::{collections::HashSet, error::Error};
+
+use crate::{enums::ScanStage, model::{endpoint::Endpoint, file_error::FileError, finding::{MapperMethod, MapperParameter, OrmFinding}}, util::{java_source::{JavaSource, MethodDeclaration, Parameter, TypeDeclaration}, FileUtil}};
+
+use super::JAVAProject;
+
+// 向上查找调用方的最大层数，超过后不再继续查找
+const MAX_CALL_DEPTH: usize = 6;
+
+/**
+ * @Struct JavaType 项目中声明的类型及其方法
+ * @Field file_path 所在的文件路径
+ * @Field source 所在的源码
+ * @Field declaration 类型声明
+ * @Field methods 类型中直接声明的方法
+ */
+struct JavaType<'a> {
+    file_path: &'a str,
+    source: &'a JavaSource,
+    declaration: TypeDeclaration,
+    methods: Vec<MethodDeclaration>,
+}
+
+impl JAVAProject {
+
+    /**
+     * @descript 读取并预处理项目中的java文件，读取失败的文件记录后跳过
+     * @param root_dir 项目根目录
+     * @param exclude_path 排除的目录
+     * @param errors 处理失败的文件
+     * @return 文件路径和预处理后的源码
+     */
+    pub fn load_java_sources(root_dir: &str, exclude_path: &Vec<String>, errors: &mut Vec<FileError>) -> Result<Vec<(String, JavaSource)>, Box<dyn Error>> {
+        let mut java_sources = vec![];
+        for java_file in FileUtil::collection_file(root_dir, &vec![String::from("java")], exclude_path)? {
+            match FileUtil::read_file(&java_file) {
+                Ok(source) => {
+                    let source = JavaSource::parse(&java_file, source);
+                    java_sources.push((java_file, source));
+                },
+                Err(err) => errors.push(FileError::new(ScanStage::Orm, &java_file, &err)),
+            }
+        }
+        Ok(java_sources)
+    }
+
+    /**
+     * @descript 根据风险点所在的namespace和statement_id关联mapper接口方法、${}引用的参数以及直接或间接调用该语句的Spring接口
+     * @param java_sources 项目中的java源码
+     * @param findings ORM风险点
+     */
+    pub fn trace_orm_findings(java_sources: &[(String, JavaSource)], findings: &mut [OrmFinding]) {
+        let types: Vec<JavaType> = java_sources.iter()
+            .flat_map(|(file_path, source)| {
+                source.types().into_iter().map(move |declaration| JavaType {
+                    file_path,
+                    source,
+                    methods: source.methods(&declaration),
+                    declaration,
+                })
+            })
+            .collect();
+        let endpoints = Self::spring_endpoints(java_sources);
+        for finding in findings.iter_mut() {
+            if finding.statement_id.is_empty() {
+                continue;
+            }
+            let Some(type_index) = types.iter().position(|java_type| java_type.declaration.full_name == finding.namespace) else {
+                continue;
+            };
+            // mapper接口中与语句id同名的方法
+            let java_type = &types[type_index];
+            if let Some(method) = java_type.methods.iter().find(|method| method.name == finding.statement_id && method.body.is_none()) {
+                finding.mapper_method = Some(Self::mapper_method(java_type, method, &finding.param));
+            }
+            let mut visited = HashSet::new();
+            Self::collect_routes(&types, type_index, &finding.statement_id, 0, &endpoints, &mut visited, &mut finding.routes);
+        }
+    }
+
+    /**
+     * @descript 获取mapper方法的签名和${}引用的参数
+     * @param java_type mapper接口
+     * @param method mapper方法
+     * @param param ${}引用的参数名，可以是参数的属性，如user.name
+     */
+    fn mapper_method(java_type: &JavaType, method: &MethodDeclaration, param: &str) -> MapperMethod {
+        let source = java_type.source;
+        let parameters = source.parameters(method.params_open);
+        let bound_name = |parameter: &Parameter| {
+            parameter.annotations.iter()
+                .find(|annotation| annotation.name == "Param")
+                .map(|annotation| source.literal_text(annotation.start, annotation.end))
+        };
+        let root = param.split(['.', '[']).next().unwrap_or_default().trim();
+        let matched = match root.is_empty() {
+            true => None,
+            false => parameters.iter().find(|parameter| bound_name(parameter).as_deref() == Some(root))
+                .or_else(|| parameters.iter().find(|parameter| bound_name(parameter).is_none() && parameter.name == root))
+                // param1、param2按参数位置引用
+                .or_else(|| root.strip_prefix("param").and_then(|index| index.parse::<usize>().ok()).and_then(|index| parameters.get(index.wrapping_sub(1))))
+                // 只有一个没有@Param的参数时，${}引用该参数或其属性
+                .or_else(|| match parameters.as_slice() {
+                    [parameter] if bound_name(parameter).is_none() => Some(parameter),
+                    _ => None,
+                }),
+        };
+        let params_close = source.matching_close(method.params_open).unwrap_or(method.params_open);
+        MapperMethod {
+            file_path: java_type.file_path.to_string(),
+            line: source.line_of(method.name_offset),
+            signature: source.source[method.name_offset..=params_close].split_whitespace().collect::<Vec<&str>>().join(" "),
+            parameter: matched.map(|parameter| MapperParameter {
+                name: parameter.name.clone(),
+                param: bound_name(parameter),
+                type_name: parameter.type_name.clone(),
+            }),
+        }
+    }
+
+    /**
+     * @descript 向上查找调用方法的代码，直到Spring的Controller
+     * @param types 项目中的类型
+     * @param type_index 方法所在的类型
+     * @param method 方法名
+     * @param depth 当前的查找层数
+     * @param endpoints 项目中的Spring接口
+     * @param visited 已经查找过的方法
+     * @param routes 找到的接口
+     */
+    fn collect_routes(types: &[JavaType], type_index: usize, method: &str, depth: usize, endpoints: &[Endpoint], visited: &mut HashSet<String>, routes: &mut Vec<String>) {
+        let java_type = &types[type_index];
+        if depth > MAX_CALL_DEPTH || !visited.insert(format!("{}.{}", java_type.declaration.full_name, method)) {
+            return;
+        }
+        // 到达处理方法时停止，Controller中的其他方法继续查找类内部的调用
+        let handler_endpoints: Vec<&Endpoint> = endpoints.iter()
+            .filter(|endpoint| endpoint.controller == java_type.declaration.full_name && endpoint.method == method)
+            .collect();
+        if !handler_endpoints.is_empty() {
+            for endpoint in handler_endpoints {
+                let route = endpoint.route();
+                if !routes.contains(&route) {
+                    routes.push(route);
+                }
+            }
+            return;
+        }
+        // 调用方可能通过实现的接口引用该类型，项目外的父类型如BaseMapper不参与查找
+        let referenced_names: Vec<&str> = std::iter::once(java_type.declaration.name.as_str())
+            .chain(java_type.declaration.supertypes.iter()
+                .filter(|name| types.iter().any(|other| other.declaration.name == **name))
+                .map(String::as_str))
+            .collect();
+        let mut callers: Vec<(usize, String)> = vec![];
+        for (caller_index, caller) in types.iter().enumerate() {
+            let source = caller.source;
+            let mut receivers: Vec<String> = Self::typed_variables(caller, &referenced_names);
+            // MyBatis-Plus的ServiceImpl<Mapper, Entity>中通过baseMapper调用mapper
+            let header: String = source.code[caller.declaration.start..caller.declaration.body_open].split_whitespace().collect();
+            if referenced_names.iter().any(|name| header.contains(&format!("ServiceImpl<{},", name))) {
+                receivers.push(String::from("baseMapper"));
+            }
+            let mut call_offsets: Vec<usize> = vec![];
+            for receiver in &receivers {
+                for start in source.words(receiver) {
+                    let rest = source.code[start + receiver.len()..].trim_start();
+                    let Some(rest) = rest.strip_prefix('.') else {
+                        continue;
+                    };
+                    let rest = rest.trim_start();
+                    if rest.strip_prefix(method).is_some_and(|rest| rest.trim_start().starts_with('(') && !rest.starts_with(|char: char| char.is_alphanumeric() || char == '_')) {
+                        call_offsets.push(start);
+                    }
+                }
+            }
+            // 同一个类中直接调用或通过this调用
+            if caller_index == type_index {
+                for start in source.words(method) {
+                    let before = source.code[..start].trim_end();
+                    let is_self_call = !before.ends_with('.') || before.strip_suffix('.').is_some_and(|before| before.trim_end().ends_with("this"));
+                    if is_self_call && source.code[start + method.len()..].trim_start().starts_with('(') {
+                        call_offsets.push(start);
+                    }
+                }
+            }
+            for offset in call_offsets {
+                if offset < caller.declaration.body_open || offset > caller.declaration.body_close {
+                    continue;
+                }
+                if let Some(caller_method) = caller.methods.iter().find(|caller_method| caller_method.body.is_some_and(|(open, close)| open < offset && offset < close)) {
+                    callers.push((caller_index, caller_method.name.clone()));
+                }
+            }
+        }
+        for (caller_index, caller_method) in callers {
+            Self::collect_routes(types, caller_index, &caller_method, depth + 1, endpoints, visited, routes);
+        }
+    }
+
+    /**
+     * @descript 查找类型中声明为指定类型的变量，包括字段、方法参数和局部变量
+     * @param java_type 要查找的类型
+     * @param type_names 变量的类型名
+     */
+    fn typed_variables(java_type: &JavaType, type_names: &[&str]) -> Vec<String> {
+        let source = java_type.source;
+        let mut variables = vec![];
+        for type_name in type_names {
+            for start in source.words(type_name) {
+                if start < java_type.declaration.body_open || start > java_type.declaration.body_close {
+                    continue;
+                }
+                let rest = source.code[start + type_name.len()..].trim_start();
+                let variable: String = rest.chars().take_while(|char| char.is_alphanumeric() || *char == '_').collect();
+                if !variable.is_empty() && !matches!(variable.as_str(), "extends" | "implements" | "instanceof") && !variables.contains(&variable) {
+                    variables.push(variable);
+                }
+            }
+        }
+        variables
+    }
+}
@@ -1,27 +1,37 @@
-use std::{fs::{File, self}, error::Error, io::Write};
+use std::{fs, error::Error};
 
 use regex::Regex;
 
-use crate::{util::{PathUtil, rule_check::RuleCheck, FileUtil}, model::{config::Config, Interfaces}, enums::Framwork::*};
+use crate::{util::{rule_check::RuleCheck, FileUtil}, model::{config::Config, Interfaces, result::ScanResult, file_error::FileError}, enums::{Framwork::{self, *}, LangType, ScanStage}, error::ScanError};
 
 use super::{Scanner, PHPProject};
 
 impl Scanner for PHPProject {
-    fn start(&self, config: &mut Config, report_file: &mut File) -> Result<Vec<String>, Box<dyn Error>>{
+    fn lang_type(&self) -> LangType {
+        LangType::PHP
+    }
+
+    fn default_exts(&self) -> Vec<String> {
+        vec![String::from("php")]
+    }
+
+    fn default_rule_pack(&self) -> String {
+        String::from("rules/php")
+    }
+
+    fn frameworks(&self) -> Vec<Framwork> {
+        vec![Laravel, ThinkPhp]
+    }
+
+    fn start(&self, config: &mut Config, result: &mut ScanResult) -> Result<Vec<String>, Box<dyn Error>>{
         // 要扫描的代码文件后缀路径收集
         let file_path_list: Vec<String> = FileUtil::collection_file(&config.work_dir, &config.scan_ext, &config.exclude_path)?;
-        let mut file_list_info: String = String::from("### 根据后缀找到文件列表:   \n\n```\n");
-        // 获取去掉前缀，只保留项目路径
-        for file_path in &PathUtil::clear_prefix(file_path_list.clone(), &config.work_dir) {
-            file_list_info.push_str(format!(" - {}\n", file_path).as_str())
-        }
-        file_list_info.push_str("\n```  \n\n");
-        report_file.write(file_list_info.as_bytes())?;
+        result.files = file_path_list.clone();
         // 接口收集
-        let interfaces: Interfaces = PHPProject::collection_interface(&config, file_path_list.clone(), report_file)?;
+        let interfaces: Interfaces = PHPProject::collection_interface(config, file_path_list.clone(), &mut result.errors)?;
         // println!("interface: {:?}", interfaces);
         // 根据扫描规则跑出来的漏洞
-        RuleCheck::start(&config.rule, file_path_list, report_file, config.use_ui)?;
+        RuleCheck::start(config, file_path_list, result)?;
         println!("[+]基于规则漏洞扫描完毕");
         Ok(interfaces)
     }
@@ -33,25 +43,25 @@ impl PHPProject {
      * @descript 收集接口
      * @param config 配置对象
      * @param 要扫描的文件绝对路径地址列表
+     * @param errors 处理失败的文件
      */
-    fn collection_interface(config: &Config, file_path_list: Vec<String>, report_file: &mut File) -> Result<Interfaces, Box<dyn Error>>{
+    fn collection_interface(config: &Config, file_path_list: Vec<String>, errors: &mut Vec<FileError>) -> Result<Interfaces, Box<dyn Error>>{
         let mut interfaces: Interfaces = Vec::new();
         // 配置选择了收集接口
         if config.collection_interface {
             // 框架代码选择
             let mut framework_interface: Interfaces = match config.framework {
-                Laravel => Self::collection_laravel_interface(file_path_list.clone())?,
-                ThinkPhp => Self::collection_thinkphp_interface(file_path_list.clone())?,
+                Laravel => Self::collection_laravel_interface(file_path_list.clone(), errors)?,
+                ThinkPhp => Self::collection_thinkphp_interface(file_path_list.clone(), errors)?,
                 None => {
                     println!("未使用开发框架，跳过特定检查");
                     vec![]
                 },
-                _ => panic!("配置中填写的框架非php框架,请检查配置文件!")
+                _ => return Err(ScanError::Config(format!("配置中填写的框架{:?}非php框架,请检查配置文件!", config.framework)).into())
             };
             // 收集原生的web处理php文件
             interfaces.append(&mut framework_interface);
             interfaces.append(&mut Self::collection_request_php(file_path_list)?);
-            report_file.write(format!("### 扫描出的接口列表:   \n```\n{:#?}\n```\n", interfaces).as_bytes())?;
             println!("[+]接口地址收集完毕");
         }
         println!("[+]要扫描的文件收集完毕");
@@ -61,14 +71,21 @@ impl PHPProject {
     /**
      * @descript 从laravel框架中提取url
      * @param 要扫描的文件列表
+     * @param errors 处理失败的文件
      * @return laravel框架的接口
      */
-    fn collection_laravel_interface(file_path_list: Vec<String>) -> Result<Interfaces, Box<dyn Error>>{
+    fn collection_laravel_interface(file_path_list: Vec<String>, errors: &mut Vec<FileError>) -> Result<Interfaces, Box<dyn Error>>{
         let mut extracted_urls: Interfaces = Vec::new();
 
         // 遍历查找接口
         for php_file in file_path_list {
-            let php_code = String::from_utf8(fs::read(php_file)?)?;
+            let php_code = match FileUtil::read_file(&php_file) {
+                Ok(php_code) => php_code,
+                Err(err) => {
+                    errors.push(FileError::new(ScanStage::Interfaces, &php_file, &err));
+                    continue;
+                },
+            };
             let lines = php_code.lines().collect::<Vec<_>>();
             let mut base_prefix = String::new();
 
@@ -97,32 +114,33 @@ impl PHPProject {
     /**
      * @descript 从thinkphp提取接口地址
      * @param 要扫描的文件列表
+     * @param errors 处理失败的文件
      * @return thinkphp框架的接口地址
      */
-    fn collection_thinkphp_interface(file_path_list: Vec<String>) -> Result<Interfaces, Box<dyn Error>> {
+    fn collection_thinkphp_interface(file_path_list: Vec<String>, errors: &mut Vec<FileError>) -> Result<Interfaces, Box<dyn Error>> {
         println!("[+]开始提取ThinkPHP框架接口");
         let mut interface_list: Interfaces = vec![];
-    
+        let route_regex = Regex::new(r#"(Route::(?:get|post|put|delete|any)\(['"]([^'"]*?)['"]|Route::prefix\(['"]([^'"]*?)['"]\)\s*->\s*group\(\s*\)\s*|Route::group\s*\(\s*\)\s*|})"#)?;
+
         // 遍历查找接口
         for php_file in file_path_list.clone() {
-            let php_code = match fs::read_to_string(&php_file) {
+            let php_code = match FileUtil::read_file(&php_file) {
                 Ok(source_code) => source_code,
                 Err(err) => {
-                    println!("[-]文件: {}读取失败,msg: {:?}", php_file, err);
+                    errors.push(FileError::new(ScanStage::Interfaces, &php_file, &err));
                     continue;
                 },
             };
-            let route_regex = Regex::new(r#"(Route::(?:get|post|put|delete|any)\(['"]([^'"]*?)['"]|Route::prefix\(['"]([^'"]*?)['"]\)\s*->\s*group\(\s*\)\s*|Route::group\s*\(\s*\)\s*|})"#)?;
     
             // 查找匹配的路由地址
             for capture in route_regex.captures_iter(&php_code) {
                 if let Some(url) = capture.get(2) {
                     interface_list.push(url.as_str().to_string());
                 } else if let Some(prefix) = capture.get(3) {
-                    if capture.get(1).unwrap().as_str() == "prefix" {
+                    if capture.get(1).map_or("", |m| m.as_str()) == "prefix" {
                         interface_list.push(prefix.as_str().to_string());
                     }
-                } else if capture.get(0).unwrap().as_str() == "}" {
+                } else if capture.get(0).map_or("", |m| m.as_str()) == "}" {
                     interface_list.pop();
                 }
             }
@@ -133,28 +151,22 @@ impl PHPProject {
             path.contains("/application/")
         });
 
-        // 直接继承自Controller的文件接口
+        let controller_regex = Regex::new(r"\bclass\s+([A-Z]\w*)\s+extends\s+Controller\b")?;
+        let path_regex = Regex::new(r"application/([a-zA-Z]+)/controller/([a-zA-Z]+)\.php")?;
+        let method_regex = Regex::new(r"\bpublic\s+function\s+([A-Za-z_]\w*)\s*\(")?;
+        // 直接继承自Controller的文件接口，读取失败的文件在上面已经记录过
         application_files.into_iter().for_each(|path|{
-            let php_code = match fs::read_to_string(&path) {
-                Ok(source_code) => source_code,
-                Err(err) => {
-                    println!("[-]{}文件: {}读取失败,msg: {:?}",line!(), path, err);
-                    String::new()
-                },
-            };
+            let php_code = FileUtil::read_file(&path).unwrap_or_default();
             // 源码不为空
             if !php_code.is_empty() {
-                let controller_regex = Regex::new(r"\bclass\s+([A-Z]\w*)\s+extends\s+Controller\b").unwrap();
                 // 当捕捉到是一个继承了Controller的接口文件
                 if let Some(captures) = controller_regex.captures(&php_code) {
                     if let Some(_class_name) = captures.get(1) {
                         let mut prefix = String::new();
                         // 提取类名
                         // let class_name = class_name.as_str();
-                        let pattern = r"application/([a-zA-Z]+)/controller/([a-zA-Z]+)\.php";
-                        let re = Regex::new(pattern).unwrap();
                         // 提取模块名和控制器名拼接接口地址
-                        if let Some(captures) = re.captures(&path) {
+                        if let Some(captures) = path_regex.captures(&path) {
                             let module = captures.get(1).map_or("", |m| m.as_str());
                             let controller = captures.get(2).map_or("", |m| m.as_str());
                             prefix.push_str(&format!("/{}/{}", module, controller.replace("Controller", "")).to_lowercase());
@@ -162,7 +174,6 @@ impl PHPProject {
                         // 当前缀不为空
                         if !prefix.is_empty(){
                             // 提取后缀的方法名
-                            let method_regex = Regex::new(r"\bpublic\s+function\s+([A-Za-z_]\w*)\s*\(").unwrap();
                             for method_captures in method_regex.captures_iter(&php_code) {
                                 if let Some(method_name) = method_captures.get(1) {
                                     let method_name = method_name.as_str();
@@ -189,9 +200,9 @@ impl PHPProject {
      */
     fn collection_request_php(file_path_list: Vec<String>) -> Result<Interfaces, Box<dyn Error>>{
         Ok(file_path_list.into_iter().filter(|file_path|{
-            let file_content = fs::read_to_string(&file_path);
-            if file_content.is_err() {return false;}
-            let file_content = file_content.unwrap();
+            let Ok(file_content) = fs::read_to_string(file_path) else {
+                return false;
+            };
             file_content.contains("$_GET") || file_content.contains("$_POST") || file_content.contains("$_REQUEST")
         }).collect())
     }
@@ -0,0 +1,64 @@
+use crate::{enums::LangType, error::ScanError, model::{config::Config, result::ScanResult}};
+
+use super::{Application, GOProject, JAVAProject, PHPProject, Scanner, ScannerRegistry};
+
+impl Default for ScannerRegistry {
+    // 注册内置的java、php、go扫描器
+    fn default() -> Self {
+        let mut registry = ScannerRegistry::empty();
+        registry.register(JAVAProject).register(PHPProject).register(GOProject);
+        registry
+    }
+}
+
+impl ScannerRegistry {
+
+    /**
+     * @descript 创建不包含任何扫描器的注册表
+     */
+    pub fn empty() -> Self {
+        ScannerRegistry { scanners: vec![] }
+    }
+
+    /**
+     * @descript 注册扫描器，已注册相同语言的扫描器时替换原有的扫描器
+     * @param scanner 实现了Scanner的扫描器
+     */
+    pub fn register(&mut self, scanner: impl Scanner + 'static) -> &mut Self {
+        let lang_type = scanner.lang_type();
+        self.scanners.retain(|registered| !registered.lang_type().is(&lang_type));
+        self.scanners.push(Box::new(scanner));
+        self
+    }
+
+    /**
+     * @descript 获取语言对应的扫描器
+     * @param lang_type 配置中的语言类型
+     */
+    pub fn get(&self, lang_type: &LangType) -> Result<&dyn Scanner, ScanError> {
+        match self.scanners.iter().find(|scanner| scanner.lang_type().is(lang_type)) {
+            Some(scanner) => Ok(scanner.as_ref()),
+            None => {
+                let languages: Vec<String> = self.scanners.iter().map(|scanner| scanner.lang_type().to_string()).collect();
+                Err(ScanError::Config(format!("不支持的语言类型{},已注册的语言为: {}", lang_type, languages.join(", "))))
+            },
+        }
+    }
+
+    /**
+     * @descript 已注册的扫描器
+     */
+    pub fn scanners(&self) -> impl Iterator<Item = &dyn Scanner> {
+        self.scanners.iter().map(|scanner| scanner.as_ref())
+    }
+
+    /**
+     * @descript 根据配置中的语言类型选择扫描器扫描项目
+     * @param config 配置信息
+     * @return ScanResult 扫描结果
+     */
+    pub fn scan(&self, config: Config) -> Result<ScanResult, ScanError> {
+        let scanner = self.get(&config.lang_type)?;
+        Application::start(scanner, config).map_err(ScanError::from)
+    }
+}
@@ -0,0 +1,513 @@
+use std::{collections::{HashMap, HashSet}, error::Error};
+
+use crate::{enums::{ParamBinding, ScanStage}, model::{endpoint::{Endpoint, EndpointParam}, file_error::FileError}, util::{java_source::{Annotation, JavaSource, MethodDeclaration, TypeDeclaration}, FileUtil}};
+
+use super::JAVAProject;
+
+// 常量引用其他常量时最多计算的次数
+const MAX_CONSTANT_PASSES: usize = 4;
+
+// 由Spring注入的请求上下文等参数，不是从请求中绑定的
+const CONTEXT_PARAMETERS: [&str; 16] = [
+    "HttpServletRequest", "HttpServletResponse", "ServletRequest", "ServletResponse", "HttpSession", "WebRequest", "NativeWebRequest", "Model",
+    "ModelMap", "ModelAndView", "BindingResult", "Errors", "RedirectAttributes", "SessionStatus", "Principal", "Locale",
+];
+
+impl JAVAProject {
+
+    /**
+     * @descript 收集Spring framework中的接口
+     * @param path_list 筛选后缀后到文件路径列表
+     * @param errors 处理失败的文件
+     * @return Vec<Endpoint> 收集到的接口
+     */
+    pub fn collection_spring(path_list: &[String], errors: &mut Vec<FileError>) -> Result<Vec<Endpoint>, Box<dyn Error>> {
+        let mut java_sources = vec![];
+        // 路径中引用的常量可能声明在其他文件中，需要读取所有的java文件
+        for path in path_list.iter().filter(|path| path.ends_with(".java")) {
+            match FileUtil::read_file(path) {
+                Ok(source) => java_sources.push((path.clone(), JavaSource::parse(path, source))),
+                Err(err) => errors.push(FileError::new(ScanStage::Interfaces, path, &err)),
+            }
+        }
+        Ok(Self::spring_endpoints(&java_sources))
+    }
+
+    /**
+     * @descript 获取Controller中处理方法对应的接口，处理方法可以使用实现的接口中声明的映射
+     * @param java_sources 项目中的java源码
+     */
+    pub fn spring_endpoints(java_sources: &[(String, JavaSource)]) -> Vec<Endpoint> {
+        let constants = Self::string_constant_values(java_sources);
+        let types: Vec<(&String, &JavaSource, TypeDeclaration, Vec<MethodDeclaration>)> = java_sources.iter()
+            .flat_map(|(file_path, source)| {
+                source.types().into_iter().map(move |declaration| {
+                    let methods = source.methods(&declaration);
+                    (file_path, source, declaration, methods)
+                })
+            })
+            .collect();
+        let mut endpoints = vec![];
+        for (file_path, source, declaration, methods) in &types {
+            let annotations = source.annotations(declaration.start, declaration.body_open);
+            let is_controller = annotations.iter().any(|annotation| annotation.name == "Controller" || annotation.name == "RestController");
+            let mut class_mapping = annotations.iter().find(|annotation| annotation.name == "RequestMapping").map(|annotation| (*source, annotation.clone()));
+            // 接口和FeignClient中的映射不是本项目提供的接口
+            if Self::is_interface(source, declaration) || annotations.iter().any(|annotation| annotation.name == "FeignClient") || (!is_controller && class_mapping.is_none()) {
+                continue;
+            }
+            let supertypes: Vec<&(&String, &JavaSource, TypeDeclaration, Vec<MethodDeclaration>)> = types.iter()
+                .filter(|(_, _, supertype, _)| declaration.supertypes.contains(&supertype.name))
+                .collect();
+            if class_mapping.is_none() {
+                class_mapping = supertypes.iter().find_map(|(_, supertype_source, supertype, _)| {
+                    supertype_source.annotations(supertype.start, supertype.body_open).into_iter()
+                        .find(|annotation| annotation.name == "RequestMapping")
+                        .map(|annotation| (*supertype_source, annotation))
+                });
+            }
+            let (class_methods, class_paths) = class_mapping
+                .map(|(class_source, annotation)| Self::request_mapping(class_source, &annotation, &constants))
+                .unwrap_or_default();
+            let class_paths = if class_paths.is_empty() { vec![String::new()] } else { class_paths };
+            for method in methods {
+                // 方法上没有映射时使用实现的接口中同名方法的映射
+                let mapping = Self::method_mapping(source, method).map(|annotation| (*source, method, annotation))
+                    .or_else(|| supertypes.iter().find_map(|(_, supertype_source, _, supertype_methods)| {
+                        supertype_methods.iter()
+                            .filter(|supertype_method| supertype_method.name == method.name)
+                            .find_map(|supertype_method| Self::method_mapping(supertype_source, supertype_method).map(|annotation| (*supertype_source, supertype_method, annotation)))
+                    }));
+                let Some((mapping_source, mapping_method, annotation)) = mapping else {
+                    continue;
+                };
+                let (mut http_methods, paths) = Self::request_mapping(mapping_source, &annotation, &constants);
+                // 类和方法上限制的请求方法合并
+                for http_method in &class_methods {
+                    if !http_methods.contains(http_method) {
+                        http_methods.push(http_method.clone());
+                    }
+                }
+                let paths = if paths.is_empty() { vec![String::new()] } else { paths };
+                let params = Self::endpoint_params(mapping_source, mapping_method, &constants);
+                for class_path in &class_paths {
+                    for path in &paths {
+                        endpoints.push(Endpoint {
+                            file_path: file_path.to_string(),
+                            line: source.line_of(method.name_offset),
+                            http_methods: http_methods.clone(),
+                            path: Self::join_path(class_path, path),
+                            controller: declaration.full_name.clone(),
+                            method: method.name.clone(),
+                            params: params.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        endpoints
+    }
+
+    /**
+     * @descript 计算项目中String常量的值，键为类名.常量名，只有一个类声明的常量同时以常量名为键
+     * @param java_sources 项目中的java源码
+     */
+    fn string_constant_values(java_sources: &[(String, JavaSource)]) -> HashMap<String, String> {
+        let declarations: Vec<(&JavaSource, String, String, usize, usize)> = java_sources.iter()
+            .flat_map(|(_, source)| {
+                let types = source.types();
+                source.string_constants().into_iter()
+                    .map(|(name, start, end)| {
+                        let type_name = types.iter().rev()
+                            .find(|declaration| declaration.body_open < start && start < declaration.body_close)
+                            .map(|declaration| declaration.name.clone())
+                            .unwrap_or_default();
+                        (source, type_name, name, start, end)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        // 多个类声明了同名常量时不能只通过常量名确定
+        let mut declared_in: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for (_, type_name, name, _, _) in &declarations {
+            declared_in.entry(name.as_str()).or_default().insert(type_name.as_str());
+        }
+        let mut constants: HashMap<String, String> = HashMap::new();
+        // 常量可以引用其他常量，重复计算直到不再变化
+        for _ in 0..MAX_CONSTANT_PASSES {
+            let mut changed = false;
+            for (source, type_name, name, start, end) in &declarations {
+                let value = source.string_values(*start, *end, &constants).concat();
+                let mut keys = vec![format!("{}.{}", type_name, name)];
+                if declared_in.get(name.as_str()).is_some_and(|types| types.len() == 1) {
+                    keys.push(name.clone());
+                }
+                for key in keys {
+                    if constants.get(&key) != Some(&value) {
+                        constants.insert(key, value.clone());
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        constants
+    }
+
+    /**
+     * @descript 判断类型是否为接口或注解
+     */
+    fn is_interface(source: &JavaSource, declaration: &TypeDeclaration) -> bool {
+        source.code[declaration.start..declaration.body_open].split(|char: char| !char.is_alphanumeric() && char != '_').any(|word| word == "interface")
+    }
+
+    /**
+     * @descript 获取方法上的映射注解
+     */
+    fn method_mapping(source: &JavaSource, method: &MethodDeclaration) -> Option<Annotation> {
+        source.annotations(method.start, method.name_offset).into_iter()
+            .find(|annotation| matches!(annotation.name.as_str(), "RequestMapping" | "GetMapping" | "PostMapping" | "PutMapping" | "DeleteMapping" | "PatchMapping"))
+    }
+
+    /**
+     * @descript 获取映射注解中的请求方法和路径，value和path可以是数组、常量或拼接的字符串
+     * @param source 注解所在的源码
+     * @param annotation 映射注解
+     * @param constants 项目中的常量
+     * @return 请求方法和路径，没有限制时为空
+     */
+    fn request_mapping(source: &JavaSource, annotation: &Annotation, constants: &HashMap<String, String>) -> (Vec<String>, Vec<String>) {
+        let paths = source.annotation_attribute(annotation, "value")
+            .or_else(|| source.annotation_attribute(annotation, "path"))
+            .map(|(start, end)| source.string_values(start, end, constants))
+            .unwrap_or_default();
+        let http_methods = match annotation.name.strip_suffix("Mapping") {
+            // method = {RequestMethod.GET, RequestMethod.POST}中全大写的单词
+            Some("Request") => source.annotation_attribute(annotation, "method")
+                .map(|(start, end)| {
+                    source.code[start..end].split(|char: char| !char.is_alphanumeric())
+                        .filter(|word| !word.is_empty() && word.chars().all(|char| char.is_ascii_uppercase()))
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Some(http_method) => vec![http_method.to_uppercase()],
+            None => vec![],
+        };
+        (http_methods, paths)
+    }
+
+    /**
+     * @descript 获取处理方法中从请求绑定的参数，忽略HttpServletRequest等由Spring注入的参数
+     * @param source 方法所在的源码
+     * @param method 处理方法
+     * @param constants 项目中的常量
+     */
+    fn endpoint_params(source: &JavaSource, method: &MethodDeclaration, constants: &HashMap<String, String>) -> Vec<EndpointParam> {
+        source.parameters(method.params_open).into_iter()
+            .filter_map(|parameter| {
+                let binding = parameter.annotations.iter().find_map(|annotation| ParamBinding::from_annotation(&annotation.name).map(|binding| (binding, annotation)));
+                let simple_type = parameter.type_name.split('<').next().unwrap_or_default().rsplit('.').next().unwrap_or_default().trim();
+                let (binding, name) = match binding {
+                    // 注解中没有指定名称时使用变量名
+                    Some((binding, annotation)) => (binding, source.annotation_attribute(annotation, "value")
+                        .or_else(|| source.annotation_attribute(annotation, "name"))
+                        .map(|(start, end)| source.string_values(start, end, constants).concat())
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or(parameter.name.clone())),
+                    None if CONTEXT_PARAMETERS.contains(&simple_type) => return None,
+                    None => (ParamBinding::Implicit, parameter.name.clone()),
+                };
+                Some(EndpointParam {
+                    name,
+                    variable: parameter.name,
+                    type_name: parameter.type_name,
+                    binding,
+                })
+            })
+            .collect()
+    }
+
+    /**
+     * @descript 拼接类和方法上的路径，合并重复的/并去掉结尾的/
+     */
+    fn join_path(class_path: &str, path: &str) -> String {
+        let mut full_path = format!("/{}/{}", class_path.trim_matches('/'), path.trim_matches('/'));
+        while full_path.contains("//") {
+            full_path = full_path.replace("//", "/");
+        }
+        match full_path.len() > 1 {
+            true => full_path.trim_end_matches('/').to_string(),
+            false => full_path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources(files: &[(&str, &str)]) -> Vec<(String, JavaSource)> {
+        files.iter().map(|(path, source)| (path.to_string(), JavaSource::parse(path, source.to_string()))).collect()
+    }
+
+    fn routes(files: &[(&str, &str)]) -> Vec<String> {
+        JAVAProject::spring_endpoints(&sources(files)).iter().map(Endpoint::route).collect()
+    }
+
+    #[test]
+    fn class_level_paths() {
+        let controller = r#"package com.demo;
+@RestController
+@RequestMapping({"/api", "/v1/"})
+public class UserController {
+    @GetMapping(path = "/list")
+    public List<User> list() { return null; }
+
+    @PostMapping
+    public void save() {}
+}
+"#;
+        assert_eq!(routes(&[("UserController.java", controller)]), vec![
+            "GET /api/list(com.demo.UserController.list)",
+            "GET /v1/list(com.demo.UserController.list)",
+            "POST /api(com.demo.UserController.save)",
+            "POST /v1(com.demo.UserController.save)",
+        ]);
+    }
+
+    #[test]
+    fn merge_class_and_method_http_methods() {
+        let controller = r#"package com.demo;
+@Controller
+@RequestMapping(value = "/order", method = RequestMethod.POST)
+public class OrderController {
+    @RequestMapping(value = {"/a", "/b"}, method = {RequestMethod.GET, RequestMethod.PUT})
+    public String update() { return ""; }
+
+    @RequestMapping("/c")
+    public String other() { return ""; }
+
+    @RequestMapping(path = "/d", method = RequestMethod.POST)
+    public String same() { return ""; }
+}
+"#;
+        assert_eq!(routes(&[("OrderController.java", controller)]), vec![
+            "GET,PUT,POST /order/a(com.demo.OrderController.update)",
+            "GET,PUT,POST /order/b(com.demo.OrderController.update)",
+            "POST /order/c(com.demo.OrderController.other)",
+            "POST /order/d(com.demo.OrderController.same)",
+        ]);
+    }
+
+    #[test]
+    fn constant_and_concatenated_paths() {
+        let paths = r#"package com.demo;
+public class ApiPath {
+    public static final String BASE = "/api";
+    public static final String USER = BASE + "/user";
+}
+"#;
+        let controller = r#"package com.demo;
+@RestController
+@RequestMapping(ApiPath.USER)
+public class UserController {
+    private static final String DETAIL = "/detail";
+
+    @GetMapping(DETAIL + "/{id}")
+    public User detail(@PathVariable("id") Long userId) { return null; }
+
+    @GetMapping(Missing.PATH)
+    public User missing() { return null; }
+}
+"#;
+        assert_eq!(routes(&[("ApiPath.java", paths), ("UserController.java", controller)]), vec![
+            "GET /api/user/detail/{id}(com.demo.UserController.detail)",
+            "GET /api/user/${Missing.PATH}(com.demo.UserController.missing)",
+        ]);
+    }
+
+    #[test]
+    fn same_constant_name_in_different_classes() {
+        let a = r#"package com.demo;
+@RestController
+@RequestMapping(A.BASE)
+public class A {
+    static final String BASE = "/a";
+    @GetMapping("/x")
+    public String x() { return ""; }
+}
+"#;
+        let b = r#"package com.demo;
+@RestController
+@RequestMapping(BASE)
+public class B {
+    static final String BASE = "/b";
+    @GetMapping(BASE + "/y")
+    public String y() { return ""; }
+    @GetMapping(Other.BASE)
+    public String other() { return ""; }
+}
+"#;
+        let expected = vec![
+            "GET /a/x(com.demo.A.x)",
+            "GET /b/b/y(com.demo.B.y)",
+            "GET /b/${Other.BASE}(com.demo.B.other)",
+        ];
+        // 结果与文件顺序无关，带类名的常量不会使用其他类中的同名常量
+        assert_eq!(routes(&[("A.java", a), ("B.java", b)]), expected);
+        assert_eq!(routes(&[("B.java", b), ("A.java", a)]), [&expected[1..], &expected[..1]].concat());
+    }
+
+    #[test]
+    fn static_import_and_inherited_constants() {
+        let paths = r#"package com.demo.api;
+public interface Paths {
+    String ADMIN = "/admin";
+    String BASE = "/paths";
+}
+"#;
+        let other = r#"package com.demo.api;
+public class Other {
+    public static final String BASE = "/other";
+}
+"#;
+        let imported = r#"package com.demo;
+import static com.demo.api.Paths.ADMIN;
+import static com.demo.api.Other.*;
+@RestController
+@RequestMapping(ADMIN)
+public class AdminController {
+    @GetMapping(BASE)
+    public String base() { return ""; }
+}
+"#;
+        let inherited = r#"package com.demo;
+@RestController
+public class PathController implements Paths {
+    @GetMapping(BASE)
+    public String base() { return ""; }
+}
+"#;
+        assert_eq!(routes(&[("Paths.java", paths), ("Other.java", other), ("AdminController.java", imported), ("PathController.java", inherited)]), vec![
+            "GET /admin/other(com.demo.AdminController.base)",
+            "GET /paths(com.demo.PathController.base)",
+        ]);
+    }
+
+    #[test]
+    fn interface_inherited_mappings() {
+        let api = r#"package com.demo;
+@RequestMapping("/user")
+public interface UserApi {
+    @PostMapping("/save")
+    void save(@RequestBody User user, HttpServletRequest request);
+}
+"#;
+        let controller = r#"package com.demo;
+@RestController
+public class UserController implements UserApi {
+    @Override
+    public void save(User user, HttpServletRequest request) {}
+
+    public void helper() {}
+}
+"#;
+        let endpoints = JAVAProject::spring_endpoints(&sources(&[("UserApi.java", api), ("UserController.java", controller)]));
+        assert_eq!(endpoints.len(), 1);
+        let endpoint = &endpoints[0];
+        assert_eq!(endpoint.route(), "POST /user/save(com.demo.UserController.save)");
+        assert_eq!(endpoint.file_path, "UserController.java");
+        assert_eq!(endpoint.line, 5);
+        assert_eq!(endpoint.params.len(), 1);
+        assert_eq!(endpoint.params[0].name, "user");
+        assert_eq!(endpoint.params[0].binding, ParamBinding::RequestBody);
+    }
+
+    #[test]
+    fn skip_feign_clients_and_interfaces() {
+        let feign = r#"package com.demo;
+@FeignClient(name = "user")
+@RequestMapping("/remote")
+public class RemoteClient {
+    @GetMapping("/get")
+    public String get() { return ""; }
+}
+"#;
+        let api = r#"package com.demo;
+@RequestMapping("/api")
+public interface Api {
+    @GetMapping("/get")
+    String get();
+}
+"#;
+        let plain = r#"package com.demo;
+public class Service {
+    @GetMapping("/get")
+    public String get() { return ""; }
+}
+"#;
+        assert!(routes(&[("RemoteClient.java", feign), ("Api.java", api), ("Service.java", plain)]).is_empty());
+    }
+
+    #[test]
+    fn endpoint_params() {
+        let controller = r#"package com.demo;
+@RestController
+public class SearchController {
+    @GetMapping("/search/{type}")
+    public List<Item> search(@PathVariable String type, @RequestParam(name = "q") String keyword, @RequestHeader("X-Token") String token, int page, Model model) { return null; }
+}
+"#;
+        let endpoints = JAVAProject::spring_endpoints(&sources(&[("SearchController.java", controller)]));
+        let params: Vec<(&str, &str, ParamBinding)> = endpoints[0].params.iter().map(|param| (param.name.as_str(), param.variable.as_str(), param.binding)).collect();
+        assert_eq!(params, vec![
+            ("type", "type", ParamBinding::PathVariable),
+            ("q", "keyword", ParamBinding::RequestParam),
+            ("X-Token", "token", ParamBinding::RequestHeader),
+            ("page", "page", ParamBinding::Implicit),
+        ]);
+        assert_eq!(endpoints[0].route(), "GET /search/{type}(com.demo.SearchController.search)");
+    }
+
+    #[test]
+    fn request_mapping() {
+        let source = JavaSource::parse("A.java", r#"class A {
+    @RequestMapping(path = {"/a", "/b"}, method = {RequestMethod.GET, RequestMethod.POST})
+    void a() {}
+    @DeleteMapping(value = "/c")
+    void c() {}
+    @RequestMapping
+    void d() {}
+}
+"#.to_string());
+        let declaration = &source.types()[0];
+        let mappings: Vec<(Vec<String>, Vec<String>)> = source.methods(declaration).iter()
+            .filter_map(|method| JAVAProject::method_mapping(&source, method))
+            .map(|annotation| JAVAProject::request_mapping(&source, &annotation, &HashMap::new()))
+            .collect();
+        assert_eq!(mappings, vec![
+            (vec!["GET".to_string(), "POST".to_string()], vec!["/a".to_string(), "/b".to_string()]),
+            (vec!["DELETE".to_string()], vec!["/c".to_string()]),
+            (vec![], vec![]),
+        ]);
+    }
+
+    #[test]
+    fn join_path() {
+        let cases = [
+            ("", "", "/"),
+            ("/", "/", "/"),
+            ("", "/list", "/list"),
+            ("/api", "", "/api"),
+            ("api/", "/list/", "/api/list"),
+            ("/api//v1", "list", "/api/v1/list"),
+            ("/api", "{id}", "/api/{id}"),
+        ];
+        for (class_path, path, full_path) in cases {
+            assert_eq!(JAVAProject::join_path(class_path, path), full_path, "{} + {}", class_path, path);
+        }
+    }
+}
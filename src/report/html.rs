@@ -0,0 +1,123 @@
+use std::{error::Error, fs::File, io::Write, time::{SystemTime, UNIX_EPOCH}};
+
+use serde::Serialize;
+
+use crate::{enums::Severity, model::{baseline::{BaselineDiff, BaselineState}, config::Config, finding::{SourceLine, Suppression}, result::ScanResult}, util::PathUtil};
+
+use super::HtmlReport;
+
+// 报告页面模板，数据以JSON形式嵌入页面，由页面脚本渲染
+const TEMPLATE: &str = include_str!("template.html");
+
+#[derive(Serialize)]
+struct HtmlData<'a> {
+    generated_at: u64,
+    file_count: usize,
+    total_line: usize,
+    rules: Vec<HtmlRule<'a>>,
+    findings: Vec<HtmlFinding<'a>>,
+    baseline: Option<&'a BaselineDiff>,
+    errors: Vec<HtmlError<'a>>,
+    warnings: Vec<HtmlError<'a>>,
+}
+
+#[derive(Serialize)]
+struct HtmlRule<'a> {
+    id: &'a str,
+    keyword: &'a str,
+    note: &'a str,
+    severity: Severity,
+    cwe: Option<&'a str>,
+    cwe_url: Option<String>,
+    owasp: Option<&'a str>,
+    references: &'a [String],
+}
+
+#[derive(Serialize)]
+struct HtmlFinding<'a> {
+    rule_id: &'a str,
+    severity: Severity,
+    file_path: String,
+    line: usize,
+    end_line: usize,
+    code: &'a str,
+    note: &'a str,
+    baseline_state: Option<BaselineState>,
+    suppression: Option<&'a Suppression>,
+    context: &'a [SourceLine],
+}
+
+#[derive(Serialize)]
+struct HtmlError<'a> {
+    file_path: String,
+    stage: &'static str,
+    message: &'a str,
+}
+
+impl HtmlReport {
+
+    /**
+     * @descript 将扫描结果写入可离线查看的单文件HTML报告
+     * @param config 配置信息
+     * @param result 扫描结果
+     * @param path HTML报告路径
+     */
+    pub fn write(config: &Config, result: &ScanResult, path: &str) -> Result<(), Box<dyn Error>> {
+        let data = HtmlData {
+            generated_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default(),
+            file_count: result.files.len(),
+            total_line: result.total_line,
+            rules: config.rule.iter().map(|rule| HtmlRule {
+                id: &rule.id,
+                keyword: &rule.keyword,
+                note: &rule.note,
+                severity: rule.severity,
+                cwe: rule.cwe.as_deref(),
+                cwe_url: rule.cwe_url(),
+                owasp: rule.owasp.as_deref(),
+                references: &rule.references,
+            }).collect(),
+            findings: result.findings.iter().chain(result.suppressed.iter()).map(|finding| HtmlFinding {
+                rule_id: &finding.rule_id,
+                severity: finding.severity,
+                file_path: PathUtil::relative_path(&finding.file_path, &config.work_dir),
+                line: finding.line,
+                end_line: finding.end_line,
+                code: &finding.code,
+                note: &finding.note,
+                baseline_state: finding.baseline_state,
+                suppression: finding.suppression.as_ref(),
+                context: &finding.context,
+            }).collect(),
+            baseline: result.baseline.as_ref(),
+            errors: result.errors.iter().map(|error| HtmlError {
+                file_path: PathUtil::relative_path(&error.file_path, &config.work_dir),
+                stage: error.stage.as_str(),
+                message: &error.message,
+            }).collect(),
+            warnings: result.warnings.iter().map(|warning| HtmlError {
+                file_path: PathUtil::relative_path(&warning.file_path, &config.work_dir),
+                stage: warning.stage.as_str(),
+                message: &warning.message,
+            }).collect(),
+        };
+        // 避免源码中的</script>提前结束数据标签
+        let data = serde_json::to_string(&data)?.replace("</", "<\\/");
+        let html = TEMPLATE
+            .replace("{{TITLE}}", &Self::escape(&format!("项目《{}》扫描结果报告", config.project_name)))
+            .replace("{{DATA}}", &data);
+        let mut file = File::create(path)?;
+        file.write_all(html.as_bytes())?;
+        Ok(())
+    }
+
+    /**
+     * @descript 转义HTML文本中的特殊字符
+     */
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
@@ -0,0 +1,216 @@
+use std::{error::Error, fs::File, io::Write, time::{SystemTime, UNIX_EPOCH}};
+
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::Serialize;
+
+use crate::{enums::{Confidence, FrameworkFilter, Framwork, LangType, RuleCondition, RuleScope, Severity, SpanKind, ORM}, model::{baseline::BaselineDiff, condition::CompositeCondition, config::Config, dependency::Dependency, endpoint::Endpoint, file_error::FileError, finding::{Finding, MapperMethod, OrmFinding}, result::ScanResult, UnauthorizedInterface}, util::PathUtil};
+
+use super::JsonReport;
+
+// 报告结构有不兼容的改动时需要升级该版本号
+const SCHEMA_VERSION: &str = "1.15.0";
+
+/**
+ * @Struct JsonReportData JSON报告的顶层结构，文件路径均为相对于work_dir的路径
+ * @Field schema_version 报告结构的版本号
+ * @Field tool 生成报告的工具信息
+ * @Field generated_at 报告生成时间，unix时间戳(秒)
+ * @Field config 本次扫描使用的配置摘要
+ * @Field rules 本次扫描加载的规则
+ * @Field files 根据后缀收集到的文件
+ * @Field interfaces 收集到的接口列表
+ * @Field endpoints Spring接口的请求方法、路径、处理方法和参数
+ * @Field dependencies 依赖文件中的组件
+ * @Field findings 根据规则命中的风险代码
+ * @Field baseline 与基线的对比结果，未指定基线时为空
+ * @Field suppressed 通过源码注释忽略的风险代码
+ * @Field mybatis_findings mybatis中${}形式的SQL注入风险点
+ * @Field hibernate_findings hibernate/JPA中拼接查询语句的SQL注入风险点
+ * @Field unauthorized 验证出的未授权接口
+ * @Field line_count 行数统计
+ * @Field errors 扫描过程中处理失败并被跳过的文件
+ * @Field warnings 扫描过程中不影响结果的警告，如跳过的非utf8编码的行
+ */
+#[derive(Serialize, JsonSchema)]
+#[schemars(title = "code_scan JSON report")]
+pub struct JsonReportData {
+    pub schema_version: String,
+    pub tool: ToolInfo,
+    pub generated_at: u64,
+    pub config: ConfigSummary,
+    pub rules: Vec<RuleSummary>,
+    pub files: Vec<String>,
+    pub interfaces: Vec<String>,
+    pub endpoints: Vec<Endpoint>,
+    pub dependencies: Vec<Dependency>,
+    pub findings: Vec<Finding>,
+    pub baseline: Option<BaselineDiff>,
+    pub suppressed: Vec<Finding>,
+    pub mybatis_findings: Vec<OrmFinding>,
+    pub hibernate_findings: Vec<OrmFinding>,
+    pub unauthorized: Vec<UnauthorizedInterface>,
+    pub line_count: LineCount,
+    pub errors: Vec<FileError>,
+    pub warnings: Vec<FileError>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ToolInfo {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize, JsonSchema, Debug)]
+pub struct ConfigSummary {
+    pub project_name: String,
+    pub lang_type: LangType,
+    pub work_dir: String,
+    pub scan_ext: Vec<String>,
+    pub exclude_path: Vec<String>,
+    pub collection_interface: bool,
+    pub framework: Framwork,
+    pub orm: ORM,
+    pub depency_file: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct RuleSummary {
+    pub id: String,
+    pub keyword: String,
+    pub note: String,
+    pub condition: RuleCondition,
+    pub severity: Severity,
+    pub confidence: Confidence,
+    pub cwe: Option<String>,
+    pub owasp: Option<String>,
+    pub tags: Vec<String>,
+    pub references: Vec<String>,
+    pub match_in: Vec<SpanKind>,
+    #[schemars(with = "String")]
+    pub scope: RuleScope,
+    pub when: Option<CompositeCondition>,
+    pub include_ext: Vec<String>,
+    pub include_paths: Vec<String>,
+    pub exclude_paths: Vec<String>,
+    pub frameworks: Vec<FrameworkFilter>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct LineCount {
+    pub total: usize,
+    pub not_null: usize,
+}
+
+impl From<&Config> for ConfigSummary {
+    fn from(config: &Config) -> Self {
+        ConfigSummary {
+            project_name: config.project_name.clone(),
+            lang_type: config.lang_type.clone(),
+            work_dir: config.work_dir.clone(),
+            scan_ext: config.scan_ext.clone(),
+            exclude_path: config.exclude_path.clone(),
+            collection_interface: config.collection_interface,
+            framework: config.framework.clone(),
+            orm: config.orm.clone(),
+            depency_file: config.depency_file.clone(),
+        }
+    }
+}
+
+impl JsonReport {
+
+    /**
+     * @descript 将扫描结果以JSON格式写入文件
+     * @param config 配置信息
+     * @param result 扫描结果
+     * @param path JSON报告路径
+     */
+    pub fn write(config: &Config, result: &ScanResult, path: &str) -> Result<(), Box<dyn Error>> {
+        let data = Self::build(config, result);
+        let mut file = File::create(path)?;
+        file.write_all(serde_json::to_string_pretty(&data)?.as_bytes())?;
+        Ok(())
+    }
+
+    /**
+     * @descript 生成JSON报告对应的JSON Schema
+     * @return RootSchema 可直接序列化输出的schema
+     */
+    pub fn schema() -> RootSchema {
+        schema_for!(JsonReportData)
+    }
+
+    /**
+     * @descript 将扫描结果转换为JSON报告结构
+     */
+    pub fn build(config: &Config, result: &ScanResult) -> JsonReportData {
+        let relative = |path: &str| PathUtil::relative_path(path, &config.work_dir);
+        let relative_orm = |finding: &OrmFinding| OrmFinding {
+            file_path: relative(&finding.file_path),
+            mapper_method: finding.mapper_method.clone().map(|mapper_method| MapperMethod {
+                file_path: relative(&mapper_method.file_path),
+                ..mapper_method
+            }),
+            ..finding.clone()
+        };
+        JsonReportData {
+            schema_version: SCHEMA_VERSION.to_string(),
+            tool: ToolInfo {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            generated_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default(),
+            config: ConfigSummary::from(config),
+            rules: config.rule.iter().map(|rule| RuleSummary {
+                id: rule.id.clone(),
+                keyword: rule.keyword.clone(),
+                note: rule.note.clone(),
+                condition: rule.condition.clone(),
+                severity: rule.severity,
+                confidence: rule.confidence,
+                cwe: rule.cwe.clone(),
+                owasp: rule.owasp.clone(),
+                tags: rule.tags.clone(),
+                references: rule.references.clone(),
+                match_in: rule.match_in.clone(),
+                scope: rule.scope,
+                when: rule.when.clone(),
+                include_ext: rule.include_ext.clone(),
+                include_paths: rule.include_paths.clone(),
+                exclude_paths: rule.exclude_paths.clone(),
+                frameworks: rule.frameworks.clone(),
+            }).collect(),
+            files: result.files.iter().map(|path| relative(path)).collect(),
+            interfaces: result.interfaces.clone(),
+            endpoints: result.endpoints.iter().map(|endpoint| Endpoint {
+                file_path: relative(&endpoint.file_path),
+                ..endpoint.clone()
+            }).collect(),
+            dependencies: result.dependencies.clone(),
+            findings: result.findings.iter().map(|finding| Finding {
+                file_path: relative(&finding.file_path),
+                ..finding.clone()
+            }).collect(),
+            baseline: result.baseline.clone(),
+            suppressed: result.suppressed.iter().map(|finding| Finding {
+                file_path: relative(&finding.file_path),
+                ..finding.clone()
+            }).collect(),
+            mybatis_findings: result.mybatis_findings.iter().map(&relative_orm).collect(),
+            hibernate_findings: result.hibernate_findings.iter().map(&relative_orm).collect(),
+            unauthorized: result.unauthorized.clone(),
+            line_count: LineCount {
+                total: result.total_line,
+                not_null: result.not_null_line,
+            },
+            errors: result.errors.iter().map(|error| FileError {
+                file_path: relative(&error.file_path),
+                ..error.clone()
+            }).collect(),
+            warnings: result.warnings.iter().map(|warning| FileError {
+                file_path: relative(&warning.file_path),
+                ..warning.clone()
+            }).collect(),
+        }
+    }
+}
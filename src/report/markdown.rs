@@ -0,0 +1,196 @@
+use std::{error::Error, fs::File, io::Write};
+
+use crate::{enums::{SpanKind, ORM}, model::{baseline::BaselineState, config::Config, finding::{Finding, OrmFinding}, result::ScanResult}, util::PathUtil};
+
+use super::{json::ConfigSummary, MarkdownReport};
+
+impl MarkdownReport {
+
+    /**
+     * @descript 将扫描结果以markdown格式写入文件
+     * @param config 配置信息
+     * @param result 扫描结果
+     * @param path markdown报告路径
+     */
+    pub fn write(config: &Config, result: &ScanResult, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(path)?;
+        file.write_all(Self::build(config, result).as_bytes())?;
+        Ok(())
+    }
+
+    /**
+     * @descript 根据扫描结果生成markdown报告内容，配置了基线时只详细列出新增的风险代码
+     */
+    pub fn build(config: &Config, result: &ScanResult) -> String {
+        let mut report = format!("# 项目《{}》扫描结果报告:  \n\n### 本次扫描加载的初始配置为:   \n```\n{:#?}\n```  \n\n", config.project_name, ConfigSummary::from(config));
+        report.push_str("### 加载规则文件列表如下:  \n\n```\n");
+        for rule in &config.rule {
+            report.push_str(&format!("- {:?}\n", rule));
+        }
+        report.push_str("\n```  \n\n");
+
+        if !result.dependencies.is_empty() {
+            report.push_str("### 组件依赖如下:\n```\n");
+            for dependcy in &result.dependencies {
+                report.push_str(&format!("- 找到组件信息: [{}.{}-version:{}]\n", dependcy.group_id, dependcy.artifact_id, dependcy.version.as_deref().unwrap_or("unknow")));
+            }
+            report.push_str("\n```\n");
+        }
+
+        report.push_str("### 根据后缀找到文件列表:   \n```\n");
+        // 获取去掉前缀，只保留项目路径
+        for file_path in &PathUtil::clear_prefix(result.files.clone(), &config.work_dir) {
+            report.push_str(&format!(" - {}\n", file_path));
+        }
+        report.push_str("\n```\n\n");
+
+        if let Some(web_xml) = &result.web_xml {
+            report.push_str("web.xml关键信息:   \n```\n");
+            for servlet in &web_xml.servlets {
+                report.push_str(&format!("[Servlet]: {} [Class] {:?}", servlet.names.join(","), servlet.classes));
+                for url in &servlet.urls {
+                    report.push_str(&format!(" [URL]: {}  \n", url));
+                }
+            }
+            for filter in &web_xml.filters {
+                report.push_str(&format!("[Filter]: [{}] [Class]: [{}] <=> [URL]: [{}]  \n", filter.name, filter.class, filter.url));
+            }
+            report.push_str("\n```\n");
+        }
+        if config.collection_interface {
+            report.push_str(&format!("### 扫描出的接口列表:   \n```\n{:#?}\n```\n", result.interfaces));
+            if !result.endpoints.is_empty() {
+                report.push_str("### Spring接口详情:   \n```\n");
+                for endpoint in &result.endpoints {
+                    report.push_str(&format!(" [-]{} [{}:{}]\n", endpoint.route(), endpoint.file_path, endpoint.line));
+                    if !endpoint.params.is_empty() {
+                        let params: Vec<String> = endpoint.params.iter().map(|param| param.declaration()).collect();
+                        report.push_str(&format!("        参数: {}\n", params.join(", ")));
+                    }
+                }
+                report.push_str("\n```\n");
+            }
+        }
+
+        Self::push_findings(&mut report, result);
+
+        match config.orm {
+            ORM::Mybatis => Self::push_orm_findings(&mut report, "mybatis", &result.mybatis_findings),
+            ORM::Hibernate => Self::push_orm_findings(&mut report, "hibernate", &result.hibernate_findings),
+            ORM::None => {},
+        }
+
+        // 处理失败被跳过的文件
+        if !result.errors.is_empty() {
+            report.push_str(&format!("### 扫描错误(共{}处):   \n```\n", result.errors.len()));
+            for error in &result.errors {
+                report.push_str(&format!(" - [{}] {}\n", error.stage.as_str(), error.message));
+            }
+            report.push_str("```\n");
+        }
+        // 不影响扫描结果的警告
+        if !result.warnings.is_empty() {
+            report.push_str(&format!("### 扫描警告(共{}处):   \n```\n", result.warnings.len()));
+            for warning in &result.warnings {
+                report.push_str(&format!(" - [{}] {}\n", warning.stage.as_str(), warning.message));
+            }
+            report.push_str("```\n");
+        }
+
+        report.push_str(&format!("\n  \n# 本次扫描共计: {}行,不为空的行数为: {}行", result.total_line, result.not_null_line));
+
+        if config.unauthorized.valid {
+            report.push_str("### 未授权接口列表:   \n```\n");
+            for interface in &result.unauthorized {
+                report.push_str(&format!(" - [Code]:{} [URL]{} \n[Body]{}", interface.code, interface.url, interface.body));
+            }
+            report.push_str("\n```\n");
+        }
+        report
+    }
+
+    /**
+     * @descript 写入ORM框架中的SQL注入风险点
+     * @param orm ORM框架名
+     * @param findings 风险点，按文件排列
+     */
+    fn push_orm_findings(report: &mut String, orm: &str, findings: &[OrmFinding]) {
+        report.push_str(&format!("### {}找到SQL注入风险点:\n```\n", orm));
+        for (index, finding) in findings.iter().enumerate() {
+            // 每个文件第一处风险点前写入文件名
+            if index == 0 || findings[index - 1].file_path != finding.file_path {
+                report.push_str(&format!(" [-]文件[{}]存在漏洞：\n", finding.file_path));
+            }
+            let statement = finding.statement();
+            match statement.is_empty() {
+                true => report.push_str(&format!("    [{}]行[{}]{}: {}\n", finding.line, finding.severity.as_str(), finding.note, finding.code)),
+                false => report.push_str(&format!("    [{}]行[{}][{}]{}: {}\n", finding.line, finding.severity.as_str(), statement, finding.note, finding.code)),
+            }
+            if !finding.remediation.is_empty() {
+                report.push_str(&format!("        修复建议: {}\n", finding.remediation));
+            }
+            if let Some(mapper_method) = &finding.mapper_method {
+                let parameter = match &mapper_method.parameter {
+                    Some(parameter) => match &parameter.param {
+                        Some(param) => format!(", 参数: @Param(\"{}\") {} {}", param, parameter.type_name, parameter.name),
+                        None => format!(", 参数: {} {}", parameter.type_name, parameter.name),
+                    },
+                    None => String::new(),
+                };
+                report.push_str(&format!("        mapper方法: {}[{}行] {}{}\n", mapper_method.file_path, mapper_method.line, mapper_method.signature, parameter));
+            }
+            for route in &finding.routes {
+                report.push_str(&format!("        调用接口: {}\n", route));
+            }
+        }
+        report.push_str("\n```\n");
+    }
+
+    /**
+     * @descript 写入规则命中的风险代码、通过注释忽略的风险代码和基线对比结果
+     */
+    fn push_findings(report: &mut String, result: &ScanResult) {
+        report.push_str("### 根据规则扫描出的风险代码:   \n\n```\n");
+        let mut last_file: Option<&str> = None;
+        // 同一行命中的多条规则合并输出，与基线一致的风险代码不再重复列出
+        let reported_findings: Vec<&Finding> = result.findings.iter()
+            .filter(|finding| finding.baseline_state != Some(BaselineState::Unchanged))
+            .collect();
+        for line_findings in reported_findings.chunk_by(|a, b| a.file_path == b.file_path && a.line == b.line && a.end_line == b.end_line) {
+            let finding = line_findings[0];
+            if last_file != Some(finding.file_path.as_str()) {
+                report.push_str(&format!("  \n  \n[!]文件[{}]找到可疑危险函数:  \n", finding.file_path));
+                last_file = Some(finding.file_path.as_str());
+            }
+            let hit_rules: Vec<String> = line_findings.iter()
+                .map(|hit| match hit.span {
+                    SpanKind::Comment => format!("[{}][{}]{}(注释中)", hit.severity.as_str(), hit.rule_id, hit.note),
+                    _ => format!("[{}][{}]{}", hit.severity.as_str(), hit.rule_id, hit.note),
+                })
+                .collect();
+            let line_range = match finding.end_line > finding.line {
+                true => format!("{}-{}", finding.line, finding.end_line),
+                false => finding.line.to_string(),
+            };
+            report.push_str(&format!(" [-] 行[{}]命中风险代码:{:?},命中规则: {}  \n\n", line_range, finding.code, hit_rules.join("; ")));
+        }
+        report.push_str("\n```  \n\n");
+        if !result.suppressed.is_empty() {
+            report.push_str(&format!("### 通过注释忽略的风险代码(共{}处):   \n\n```\n", result.suppressed.len()));
+            for finding in &result.suppressed {
+                if let Some(suppression) = &finding.suppression {
+                    let scope = if suppression.file_level { "文件级忽略" } else { "忽略" };
+                    report.push_str(&format!(" [~] 文件[{}]行[{}]规则: [{}] {}标记行[{}] 原因: [{}]  \n", finding.file_path, finding.line, finding.rule_id, scope, suppression.line, suppression.reason));
+                }
+            }
+            report.push_str("\n```  \n\n");
+        }
+        if let Some(diff) = &result.baseline {
+            report.push_str(&format!("### 与基线对比结果:   \n\n```\n[*]基线文件: {}  \n[*]新增: {}处, 未变化(已省略): {}处, 已修复: {}处  \n", diff.baseline_file, diff.new, diff.unchanged, diff.fixed.len()));
+            for entry in &diff.fixed {
+                report.push_str(&format!(" [+] 已修复: 文件[{}]原行[{}]代码:{:?} 规则: [{}]  \n", entry.file_path, entry.line, entry.code, entry.rule_id));
+            }
+            report.push_str("\n```  \n\n");
+        }
+    }
+}
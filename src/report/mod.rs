@@ -0,0 +1,12 @@
+pub mod sarif;
+pub mod json;
+pub mod html;
+pub mod markdown;
+
+pub struct SarifReport;
+
+pub struct JsonReport;
+
+pub struct HtmlReport;
+
+pub struct MarkdownReport;
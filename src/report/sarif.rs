@@ -0,0 +1,355 @@
+use std::{collections::HashMap, error::Error, fs::File, io::Write, path::Path};
+
+use serde::Serialize;
+
+use crate::{enums::Severity, model::{baseline::BaselineState, config::Config, result::ScanResult}, util::PathUtil};
+
+use super::SarifReport;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+// 源码根目录在SARIF中的别名，查看器通过originalUriBaseIds还原绝对路径
+const SRCROOT: &str = "SRCROOT";
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    original_uri_base_ids: HashMap<&'static str, ArtifactLocation>,
+    invocations: Vec<Invocation>,
+    results: Vec<SarifResult>,
+}
+
+// 本次扫描的执行情况，处理失败的文件作为通知输出
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Invocation {
+    execution_successful: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_execution_notifications: Vec<Notification>,
+}
+
+#[derive(Serialize)]
+struct Notification {
+    level: &'static str,
+    message: Message,
+    locations: Vec<NotificationLocation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationLocation {
+    physical_location: NotificationPhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationPhysicalLocation {
+    artifact_location: ArtifactLocation,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: String,
+    short_description: Message,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    default_configuration: Configuration,
+    properties: RuleProperties,
+}
+
+#[derive(Serialize)]
+struct RuleProperties {
+    tags: Vec<String>,
+    precision: &'static str,
+    // GitHub code scanning根据该值展示安全等级
+    #[serde(rename = "security-severity")]
+    security_severity: &'static str,
+}
+
+#[derive(Serialize)]
+struct Configuration {
+    level: &'static str,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    // 规则已不在本次加载的规则中时不输出，避免指向其他规则
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    partial_fingerprints: PartialFingerprints,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    baseline_state: Option<BaselineState>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suppressions: Vec<SarifSuppression>,
+}
+
+// 源码注释中忽略的结果
+#[derive(Serialize)]
+struct SarifSuppression {
+    kind: &'static str,
+    justification: String,
+}
+
+// 与行号无关的指纹，便于GitHub等平台跨版本追踪同一处风险代码
+#[derive(Serialize)]
+struct PartialFingerprints {
+    #[serde(rename = "codeScan/v1")]
+    code_scan_v1: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    end_line: usize,
+    snippet: Message,
+}
+
+impl SarifReport {
+
+    /**
+     * @descript 将扫描结果以SARIF 2.1.0格式写入文件
+     * @param config 配置信息，提供规则列表和项目根目录
+     * @param result 扫描结果
+     * @param path SARIF报告路径
+     */
+    pub fn write(config: &Config, result: &ScanResult, path: &str) -> Result<(), Box<dyn Error>> {
+        let log = Self::build(config, result)?;
+        let mut file = File::create(path)?;
+        file.write_all(serde_json::to_string_pretty(&log)?.as_bytes())?;
+        Ok(())
+    }
+
+    /**
+     * @descript 根据规则和命中结果构建SARIF日志对象
+     */
+    fn build(config: &Config, result: &ScanResult) -> Result<SarifLog, Box<dyn Error>> {
+        let rules: Vec<ReportingDescriptor> = config.rule.iter().map(|rule| {
+            let mut tags = vec![String::from("security")];
+            tags.extend(rule.tags.iter().cloned());
+            if let Some(cwe) = &rule.cwe {
+                tags.push(format!("external/cwe/{}", cwe.to_lowercase()));
+            }
+            if let Some(owasp) = &rule.owasp {
+                tags.push(format!("external/owasp/{}", owasp));
+            }
+            ReportingDescriptor {
+                id: rule.id.clone(),
+                short_description: Message { text: rule.note.clone() },
+                help_uri: rule.references.first().cloned().or_else(|| rule.cwe_url()),
+                default_configuration: Configuration { level: Self::level(rule.severity) },
+                properties: RuleProperties {
+                    tags,
+                    precision: rule.confidence.as_str(),
+                    security_severity: Self::security_severity(rule.severity),
+                },
+            }
+        }).collect();
+        let rule_index: HashMap<&str, usize> = rules.iter().enumerate().map(|(index, rule)| (rule.id.as_str(), index)).collect();
+
+        // 通过注释忽略的结果同样输出，由平台根据suppressions展示为已忽略
+        let results = result.findings.iter().chain(result.suppressed.iter()).map(|finding| {
+            SarifResult {
+                rule_id: finding.rule_id.clone(),
+                rule_index: rule_index.get(finding.rule_id.as_str()).copied(),
+                level: Self::level(finding.severity),
+                message: Message { text: finding.note.clone() },
+                locations: vec![Location {
+                    physical_location: PhysicalLocation {
+                        artifact_location: ArtifactLocation {
+                            uri: Self::encode_uri(&PathUtil::relative_path(&finding.file_path, &config.work_dir)),
+                            uri_base_id: Some(SRCROOT),
+                        },
+                        region: Region {
+                            start_line: finding.line,
+                            end_line: finding.end_line,
+                            snippet: Message { text: finding.code.clone() },
+                        },
+                    },
+                }],
+                partial_fingerprints: PartialFingerprints { code_scan_v1: finding.fingerprint.clone() },
+                baseline_state: finding.baseline_state,
+                suppressions: finding.suppression.iter().map(|suppression| SarifSuppression {
+                    kind: "inSource",
+                    justification: suppression.reason.clone(),
+                }).collect(),
+            }
+        }).collect();
+
+        // 失败的文件已被跳过，有失败的文件时扫描结果不完整，警告不影响扫描结果
+        let notifications = result.errors.iter().map(|error| ("error", error))
+            .chain(result.warnings.iter().map(|warning| ("warning", warning)));
+        let tool_execution_notifications = notifications.map(|(level, error)| Notification {
+            level,
+            message: Message { text: format!("[{}] {}", error.stage.as_str(), error.message) },
+            locations: vec![NotificationLocation {
+                physical_location: NotificationPhysicalLocation {
+                    artifact_location: ArtifactLocation {
+                        uri: Self::encode_uri(&PathUtil::relative_path(&error.file_path, &config.work_dir)),
+                        uri_base_id: Some(SRCROOT),
+                    },
+                },
+            }],
+        }).collect();
+
+        // 项目根目录的绝对路径，必须以/结尾
+        let root = std::fs::canonicalize(&config.work_dir).unwrap_or_else(|_| Path::new(&config.work_dir).to_path_buf());
+        let mut root_uri = Self::file_uri(&root.to_string_lossy());
+        if !root_uri.ends_with('/') {
+            root_uri.push('/');
+        }
+        let mut original_uri_base_ids = HashMap::new();
+        original_uri_base_ids.insert(SRCROOT, ArtifactLocation { uri: root_uri, uri_base_id: None });
+
+        Ok(SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: "https://github.com/getpanic/code_scan",
+                        rules,
+                    },
+                },
+                original_uri_base_ids,
+                invocations: vec![Invocation { execution_successful: result.errors.is_empty(), tool_execution_notifications }],
+                results,
+            }],
+        })
+    }
+
+    /**
+     * @descript 严重程度对应的SARIF level
+     */
+    fn level(severity: Severity) -> &'static str {
+        match severity {
+            Severity::Critical | Severity::High => "error",
+            Severity::Medium => "warning",
+            Severity::Low | Severity::Info => "note",
+        }
+    }
+
+    /**
+     * @descript 严重程度对应的CVSS分值，用于security-severity属性
+     */
+    fn security_severity(severity: Severity) -> &'static str {
+        match severity {
+            Severity::Critical => "9.5",
+            Severity::High => "8.0",
+            Severity::Medium => "5.5",
+            Severity::Low => "3.0",
+            Severity::Info => "0.0",
+        }
+    }
+
+    /**
+     * @descript 将绝对路径转换为file协议的URI，windows路径C:\src转换为file:///C:/src，UNC路径\\server\share转换为file://server/share
+     * @param path 绝对路径，windows下canonicalize得到的路径带有\\?\前缀
+     */
+    fn file_uri(path: &str) -> String {
+        let path = path.replace('\\', "/");
+        let path = match (path.strip_prefix("//?/UNC/"), path.strip_prefix("//?/")) {
+            (Some(unc), _) => format!("//{}", unc),
+            (None, Some(verbatim)) => verbatim.to_string(),
+            (None, None) => path.clone(),
+        };
+        match (path.starts_with("//"), path.starts_with('/')) {
+            // UNC路径的主机名作为URI的authority
+            (true, _) => format!("file:{}", Self::encode_uri(&path)),
+            (false, true) => format!("file://{}", Self::encode_uri(&path)),
+            // 带盘符的windows路径
+            (false, false) => format!("file:///{}", Self::encode_uri(&path)),
+        }
+    }
+
+    /**
+     * @descript 对路径中URI不允许直接出现的字符进行百分号编码
+     */
+    fn encode_uri(path: &str) -> String {
+        let mut encoded = String::with_capacity(path.len());
+        for byte in path.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => encoded.push(byte as char),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_uri() {
+        let cases = [
+            ("/home/user/project", "file:///home/user/project"),
+            ("/home/user/my project", "file:///home/user/my%20project"),
+            ("C:\\src\\project", "file:///C:/src/project"),
+            ("\\\\?\\C:\\src\\project", "file:///C:/src/project"),
+            ("\\\\server\\share\\project", "file://server/share/project"),
+            ("\\\\?\\UNC\\server\\share\\project", "file://server/share/project"),
+        ];
+        for (path, uri) in cases {
+            assert_eq!(SarifReport::file_uri(path), uri, "{}", path);
+        }
+    }
+}
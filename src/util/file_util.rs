@@ -1,42 +1,31 @@
 use std::{fs, error::Error};
 
+use crate::error::ScanError;
+
 use walkdir::WalkDir;
 
 use super::FileUtil;
 
 impl FileUtil {
     /**
-     * @descript 从路径读取文件，失败则返回空字符串
+     * @descript 从路径读取utf8编码的文件
      * @param path 文件路径
-     * @return String 文件内容字符串
+     * @return String 文件内容字符串，读取失败或不是utf8编码时返回错误
      */
-    pub fn read_file_by_path(path: &str) -> String{
-        match fs::read(path) {
-            Ok(binary) => {
-                match String::from_utf8(binary) {
-                    Ok(content) => content,
-                    Err(_) => {
-                        println!("文件[{}]不是utf8格式,读取失败", path);
-                        String::new()
-                    },
-                }
-            },
-            Err(_) => {
-                println!("文件[{}]读取失败", path);
-                String::new()
-            },
-        }
+    pub fn read_file(path: &str) -> Result<String, ScanError> {
+        let binary = fs::read(path).map_err(|err| ScanError::io(path, err))?;
+        String::from_utf8(binary).map_err(|_| ScanError::parse(path, "不是utf8格式"))
     }
 
-    /**
-     * @descript 获取引入的文件的绝对路径
-     * @param 配置路径 引入文件路径
+    // /**
+    //  * @descript 获取引入的文件的绝对路径
+    //  * @param 配置路径 引入文件路径
     //  */
     // pub fn get_absolute_path(config_xml_path: &str, include_file: &str) -> Result<PathBuf, Box<dyn Error>> {
     //     let binding = PathBuf::from(config_xml_path);
     //     let config_xml_dir = binding.parent().unwrap();
     //     let include_path = Path::new(include_file);
-    
+    //
     //     if include_path.is_absolute() {
     //         Ok(include_path.to_path_buf())
     //     } else {
@@ -67,14 +56,14 @@ impl FileUtil {
             }
         }
 
-        paths = paths.into_iter().filter(|path| {
+        paths.retain(|path| {
             for exclute in exclude_path {
                 if path.contains(exclute) {
                     return false
                 }
             }
             true
-        }).collect();
+        });
         Ok(paths)
     }
 }
\ No newline at end of file
@@ -0,0 +1,921 @@
+use std::collections::HashMap;
+
+use crate::enums::{LangType, SpanKind};
+
+use super::lexer::Lexer;
+
+/**
+ * @Struct JavaSource 预处理后的java源码，用于在源码结构上做轻量的分析
+ * @Field source 原始源码
+ * @Field code 与source按字节对齐的代码，注释替换为空格，字符串只保留首尾的引号，内容替换为空格
+ * @Field line_starts 每行起始的字节位置
+ */
+pub struct JavaSource {
+    pub source: String,
+    pub code: String,
+    line_starts: Vec<usize>,
+}
+
+/**
+ * @Struct TypeDeclaration 源码中声明的类型
+ * @Field name 类型名
+ * @Field full_name 全限定名，内部类以.连接
+ * @Field start 声明的起始位置，包含前面的注解
+ * @Field body_open 类型体起始大括号的位置
+ * @Field body_close 类型体结束大括号的位置
+ * @Field supertypes 继承和实现的类型，去掉了包名和泛型参数
+ */
+#[derive(Debug, Clone)]
+pub struct TypeDeclaration {
+    pub name: String,
+    pub full_name: String,
+    pub start: usize,
+    pub body_open: usize,
+    pub body_close: usize,
+    pub supertypes: Vec<String>,
+}
+
+/**
+ * @Struct MethodDeclaration 类型中声明的方法
+ * @Field name 方法名
+ * @Field start 声明的起始位置，包含前面的注解
+ * @Field name_offset 方法名的位置
+ * @Field params_open 参数列表起始括号的位置
+ * @Field body 方法体起止大括号的位置，接口和抽象方法为空
+ */
+#[derive(Debug, Clone)]
+pub struct MethodDeclaration {
+    pub name: String,
+    pub start: usize,
+    pub name_offset: usize,
+    pub params_open: usize,
+    pub body: Option<(usize, usize)>,
+}
+
+/**
+ * @Struct Parameter 方法参数
+ * @Field annotations 参数上的注解
+ * @Field type_name 参数类型
+ * @Field name 参数名
+ */
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub annotations: Vec<Annotation>,
+    pub type_name: String,
+    pub name: String,
+}
+
+/**
+ * @Struct Annotation 注解
+ * @Field name 注解名，不含包名
+ * @Field start 注解@的位置
+ * @Field end 注解的结束位置
+ * @Field arguments 注解参数起始括号的位置，没有参数时为空
+ */
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+    pub arguments: Option<usize>,
+}
+
+impl JavaSource {
+
+    /**
+     * @descript 预处理java源码，去掉注释和字符串内容后可以直接在代码上匹配括号、逗号和分号
+     * @param file_path 文件路径
+     * @param source 源码内容
+     */
+    pub fn parse(file_path: &str, source: String) -> Self {
+        let mut lexer = Lexer::for_file(&LangType::JAVA, file_path);
+        let mut code = String::with_capacity(source.len());
+        let mut line_starts = vec![];
+        let mut start = 0;
+        for raw_line in source.split_inclusive('\n') {
+            line_starts.push(start);
+            start += raw_line.len();
+            let line = raw_line.trim_end_matches(['\n', '\r']);
+            let spans = lexer.next_line(line);
+            for (offset, char) in line.char_indices() {
+                match spans.kind_at(offset) {
+                    SpanKind::Code => code.push(char),
+                    SpanKind::Comment => Self::blank(&mut code, char),
+                    // 字符串只保留起止的引号
+                    SpanKind::String => {
+                        let end = offset + char.len_utf8();
+                        let is_edge = offset == 0 || spans.kind_at(offset - 1) != SpanKind::String || end >= line.len() || spans.kind_at(end) != SpanKind::String;
+                        match is_edge && (char == '"' || char == '\'') {
+                            true => code.push(char),
+                            false => Self::blank(&mut code, char),
+                        }
+                    },
+                }
+            }
+            code.push_str(&raw_line[line.len()..]);
+        }
+        JavaSource { source, code, line_starts }
+    }
+
+    // 按字节数替换为空格，保持与源码对齐
+    fn blank(code: &mut String, char: char) {
+        code.extend(std::iter::repeat_n(' ', char.len_utf8()));
+    }
+
+    /**
+     * @descript 获取字节位置所在的行号，从1开始
+     */
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset).max(1)
+    }
+
+    /**
+     * @descript 获取指定行去掉首尾空白后的源码
+     * @param line 行号，从1开始
+     */
+    pub fn line_text(&self, line: usize) -> String {
+        self.source.lines().nth(line.saturating_sub(1)).unwrap_or_default().trim().to_string()
+    }
+
+    /**
+     * @descript 查找与起始括号匹配的结束括号
+     * @param open 起始括号(、[或{的字节位置
+     * @return 结束括号的字节位置，未闭合时为空
+     */
+    pub fn matching_close(&self, open: usize) -> Option<usize> {
+        let mut depth = 0;
+        for (offset, char) in self.code[open..].char_indices() {
+            match char {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(open + offset);
+                    }
+                },
+                _ => {},
+            }
+        }
+        None
+    }
+
+    /**
+     * @descript 获取方法调用或注解的参数
+     * @param open 参数列表起始括号的字节位置
+     * @return 每个参数的起止字节位置，不包含两侧的空白
+     */
+    pub fn arguments(&self, open: usize) -> Vec<(usize, usize)> {
+        let Some(close) = self.matching_close(open) else {
+            return vec![];
+        };
+        self.split_top_level(open + 1, close, ',')
+    }
+
+    /**
+     * @descript 获取语句的结束位置
+     * @param start 语句中的字节位置
+     * @return 语句结尾分号的字节位置，找不到时为代码结尾
+     */
+    pub fn statement_end(&self, start: usize) -> usize {
+        let mut depth = 0;
+        for (offset, char) in self.code[start..].char_indices() {
+            match char {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth == 0 => return start + offset,
+                ')' | ']' | '}' => depth -= 1,
+                ';' if depth == 0 => return start + offset,
+                _ => {},
+            }
+        }
+        self.code.len()
+    }
+
+    /**
+     * @descript 获取字节位置所在的类成员(通常是方法体)的起始位置，用于把变量的查找限制在同一个方法内
+     * @return 第二层大括号的位置，不在类成员中时为0
+     */
+    pub fn member_start(&self, offset: usize) -> usize {
+        let mut opened: Vec<usize> = vec![];
+        for (index, char) in self.code[..offset].char_indices() {
+            match char {
+                '{' => opened.push(index),
+                '}' => { opened.pop(); },
+                _ => {},
+            }
+        }
+        opened.get(1).copied().unwrap_or_default()
+    }
+
+    /**
+     * @descript 获取字符串表达式中拼接进来的非常量部分，支持+拼接、String.format的参数和StringBuilder/StringBuffer的append
+     * @param start 表达式的起始字节位置
+     * @param end 表达式的结束字节位置
+     * @return 拼接进来的变量、方法调用等表达式，全部为字面量或常量时为空
+     */
+    pub fn dynamic_parts(&self, start: usize, end: usize) -> Vec<String> {
+        let parts = self.split_top_level(start, end, '+');
+        let mut dynamic_parts = vec![];
+        for (part_start, part_end) in &parts {
+            let part = &self.code[*part_start..*part_end];
+            let mut matched_call = false;
+            for open in Self::builder_calls(part) {
+                matched_call = true;
+                let open = part_start + open;
+                // String.format的第一个参数是格式字符串
+                let skip = if self.code[..open].trim_end().ends_with("format") { 1 } else { 0 };
+                for (arg_start, arg_end) in self.arguments(open).into_iter().skip(skip) {
+                    // 参数本身是拼接的字符串时只取其中的非常量部分
+                    let mut arg_parts = self.dynamic_parts(arg_start, arg_end);
+                    if arg_parts.is_empty() && !Self::is_constant(&self.code[arg_start..arg_end]) && self.split_top_level(arg_start, arg_end, '+').len() == 1 {
+                        arg_parts.push(self.expression(arg_start, arg_end));
+                    }
+                    dynamic_parts.append(&mut arg_parts);
+                }
+            }
+            if !matched_call && parts.len() > 1 && !Self::is_constant(part) {
+                dynamic_parts.push(self.expression(*part_start, *part_end));
+            }
+        }
+        dynamic_parts
+    }
+
+    /**
+     * @descript 获取作为SQL使用的表达式中拼接进来的非常量部分，表达式是变量时在同一个方法中查找该变量的拼接过程
+     * @param start 表达式的起始字节位置
+     * @param end 表达式的结束字节位置
+     */
+    pub fn spliced_parts(&self, start: usize, end: usize) -> Vec<String> {
+        let dynamic_parts = self.dynamic_parts(start, end);
+        if !dynamic_parts.is_empty() {
+            return dynamic_parts;
+        }
+        let variable = self.code[start..end].trim_end_matches("toString()").trim_end().trim_end_matches('.').trim();
+        match !variable.is_empty() && variable.chars().all(|char| char.is_alphanumeric() || char == '_') {
+            true => self.variable_parts(variable, start),
+            false => vec![],
+        }
+    }
+
+    /**
+     * @descript 在方法中查找字符串变量的拼接过程：赋值、+=和StringBuilder的append
+     * @param variable 变量名
+     * @param before 使用该变量的位置，只查找该位置之前同一个方法中的代码
+     * @return 拼接进该变量的非常量部分
+     */
+    pub fn variable_parts(&self, variable: &str, before: usize) -> Vec<String> {
+        let member_start = self.member_start(before);
+        let mut dynamic_parts = vec![];
+        for start in self.words(variable).into_iter().filter(|start| *start >= member_start && *start < before) {
+            let rest = &self.code[start + variable.len()..before];
+            let operator_start = start + variable.len() + (rest.len() - rest.trim_start().len());
+            let rest = rest.trim_start();
+            let end = self.statement_end(start);
+            if rest.starts_with("+=") {
+                // +=的右侧整体都是拼接进来的内容
+                let value_start = operator_start + 2;
+                let mut parts = self.dynamic_parts(value_start, end);
+                if parts.is_empty() && !Self::is_constant(&self.code[value_start..end]) {
+                    parts.push(self.expression(value_start, end));
+                }
+                dynamic_parts.append(&mut parts);
+            } else if rest.starts_with('=') && !rest.starts_with("==") {
+                dynamic_parts.append(&mut self.dynamic_parts(operator_start + 1, end));
+            } else if rest.strip_prefix('.').is_some_and(|rest| rest.trim_start().starts_with("append")) {
+                // append链交给dynamic_parts处理
+                dynamic_parts.append(&mut self.dynamic_parts(start, end));
+            }
+        }
+        dynamic_parts
+    }
+
+    /**
+     * @descript 获取方法体中所有字符串表达式拼接进来的非常量部分，包括赋值、return和方法调用的参数，用于检查拼接SQL的方法
+     * @param body_open 方法体起始大括号的字节位置
+     */
+    pub fn method_spliced_parts(&self, body_open: usize) -> Vec<String> {
+        let body_close = self.matching_close(body_open).unwrap_or(self.code.len());
+        let mut dynamic_parts: Vec<String> = vec![];
+        let mut segment_start = body_open + 1;
+        for (offset, char) in self.code[body_open + 1..body_close].char_indices() {
+            if !matches!(char, ';' | '{' | '}') {
+                continue;
+            }
+            let segment_end = body_open + 1 + offset;
+            let mut ranges = vec![];
+            // 语句本身去掉return和赋值的左侧
+            let segment = &self.code[segment_start..segment_end];
+            let expression_start = match segment.trim_start().strip_prefix("return") {
+                Some(rest) => segment_end - rest.len(),
+                None => match Self::assignment_operator(segment) {
+                    Some(index) => segment_start + index + 1,
+                    None => segment_start,
+                },
+            };
+            ranges.push((expression_start, segment_end));
+            // 语句中方法调用的参数
+            for (index, char) in segment.char_indices() {
+                if char != '(' {
+                    continue;
+                }
+                if self.matching_close(segment_start + index).is_some_and(|close| close < segment_end) {
+                    ranges.append(&mut self.arguments(segment_start + index));
+                }
+            }
+            for (start, end) in ranges {
+                let expression = &self.code[start..end];
+                // 只检查字符串表达式
+                if !expression.contains('"') && Self::builder_calls(expression).is_empty() {
+                    continue;
+                }
+                for part in self.dynamic_parts(start, end) {
+                    if !dynamic_parts.contains(&part) {
+                        dynamic_parts.push(part);
+                    }
+                }
+            }
+            segment_start = segment_end + 1;
+        }
+        dynamic_parts
+    }
+
+    /**
+     * @descript 获取文件声明的包名
+     */
+    pub fn package_name(&self) -> String {
+        self.words("package").into_iter()
+            .next()
+            .map(|start| {
+                let end = self.statement_end(start);
+                self.code[start + "package".len()..end].split_whitespace().collect::<String>()
+            })
+            .unwrap_or_default()
+    }
+
+    /**
+     * @descript 获取静态导入的类型名和成员名，如import static com.demo.ApiPath.USER;中为(ApiPath, USER)，导入全部成员时成员名为空
+     */
+    pub fn static_imports(&self) -> Vec<(String, Option<String>)> {
+        self.words("import").into_iter()
+            .filter_map(|start| {
+                let statement = self.code[start + "import".len()..self.statement_end(start)].trim_start();
+                let path: String = statement.strip_prefix("static")?.split_whitespace().collect();
+                let (type_path, member) = path.rsplit_once('.')?;
+                let type_name = type_path.rsplit('.').next().unwrap_or(type_path).to_string();
+                Some((type_name, (member != "*").then(|| member.to_string())))
+            })
+            .collect()
+    }
+
+    /**
+     * @descript 获取源码中声明的类型，按声明位置排序，内部类排在外部类之后
+     */
+    pub fn types(&self) -> Vec<TypeDeclaration> {
+        let mut types: Vec<TypeDeclaration> = vec![];
+        for keyword in ["class", "interface", "enum", "record"] {
+            for keyword_start in self.words(keyword) {
+                let name_start = keyword_start + keyword.len();
+                let rest = &self.code[name_start..];
+                let name_start = name_start + (rest.len() - rest.trim_start().len());
+                let name: String = self.code[name_start..].chars().take_while(|char| char.is_alphanumeric() || *char == '_').collect();
+                let Some(body_open) = self.code[name_start..].find(['{', ';']).map(|index| name_start + index) else {
+                    continue;
+                };
+                // 排除Foo.class等写法，类型声明中间不会出现分号
+                if name.is_empty() || !self.code[body_open..].starts_with('{') || self.code[..keyword_start].trim_end().ends_with('.') {
+                    continue;
+                }
+                let header = &self.code[name_start + name.len()..body_open];
+                let supertypes = ["extends", "implements"].iter()
+                    .filter_map(|keyword| header.find(keyword).map(|index| &header[index + keyword.len()..]))
+                    .flat_map(|types| {
+                        let types = types.split("implements").next().unwrap_or(types);
+                        Self::strip_generics(types).split(',').map(|name| name.trim().rsplit('.').next().unwrap_or_default().to_string()).collect::<Vec<String>>()
+                    })
+                    .filter(|name| !name.is_empty())
+                    .collect();
+                types.push(TypeDeclaration {
+                    name,
+                    full_name: String::new(),
+                    start: self.declaration_start(keyword_start),
+                    body_open,
+                    body_close: self.matching_close(body_open).unwrap_or(self.code.len()),
+                    supertypes,
+                });
+            }
+        }
+        types.sort_by_key(|declaration| declaration.body_open);
+        // 全限定名由包名和外层类型名组成
+        let package_name = self.package_name();
+        for index in 0..types.len() {
+            let mut names: Vec<String> = types[..index].iter()
+                .filter(|outer| outer.body_open < types[index].body_open && types[index].body_close < outer.body_close)
+                .map(|outer| outer.name.clone())
+                .collect();
+            names.push(types[index].name.clone());
+            if !package_name.is_empty() {
+                names.insert(0, package_name.clone());
+            }
+            types[index].full_name = names.join(".");
+        }
+        types
+    }
+
+    /**
+     * @descript 获取字节位置所在的类型的全限定名，内部类以.连接
+     */
+    pub fn type_name_at(&self, offset: usize) -> String {
+        self.types().into_iter()
+            .rev()
+            .find(|declaration| declaration.body_open < offset && offset < declaration.body_close)
+            .map(|declaration| declaration.full_name)
+            .unwrap_or_default()
+    }
+
+    /**
+     * @descript 获取类型中直接声明的方法，不包含内部类和匿名类中的方法
+     * @param declaration 类型声明
+     */
+    pub fn methods(&self, declaration: &TypeDeclaration) -> Vec<MethodDeclaration> {
+        let mut methods = vec![];
+        let mut member_start = declaration.body_open + 1;
+        // 成员中注解之后的部分，用于区分字段的初始值
+        let mut declaration_start = member_start;
+        let mut pos = declaration.body_open + 1;
+        while pos < declaration.body_close {
+            let Some(char) = self.code[pos..].chars().next() else {
+                break;
+            };
+            match char {
+                ';' | '}' => {
+                    pos += 1;
+                    member_start = pos;
+                    declaration_start = pos;
+                },
+                // 成员中的大括号是内部类、初始化块或数组初始值，整体跳过
+                '{' => {
+                    pos = self.matching_close(pos).unwrap_or(declaration.body_close) + 1;
+                    member_start = pos;
+                    declaration_start = pos;
+                },
+                '(' => {
+                    let head = &self.code[member_start..pos];
+                    let close = self.matching_close(pos).unwrap_or(declaration.body_close);
+                    let name = Self::trailing_word(head).unwrap_or_default();
+                    let name_offset = member_start + head.trim_end().len() - name.len();
+                    let before_name = self.code[..name_offset].trim_end();
+                    // 注解的参数
+                    if before_name.ends_with('@') {
+                        pos = close + 1;
+                        declaration_start = pos;
+                        continue;
+                    }
+                    // 字段的初始值
+                    if name.is_empty() || before_name.ends_with('.') || before_name.ends_with("new") || self.code[declaration_start..pos].contains('=') {
+                        pos = close + 1;
+                        continue;
+                    }
+                    // 参数列表后可能有throws声明，之后是方法体或分号
+                    let Some(end) = self.code[close..declaration.body_close].find(['{', ';']).map(|index| close + index) else {
+                        break;
+                    };
+                    let body = match self.code[end..].starts_with('{') {
+                        true => Some((end, self.matching_close(end).unwrap_or(declaration.body_close))),
+                        false => None,
+                    };
+                    methods.push(MethodDeclaration {
+                        name,
+                        start: self.declaration_start(name_offset).max(member_start),
+                        name_offset,
+                        params_open: pos,
+                        body,
+                    });
+                    pos = body.map(|(_, body_close)| body_close).unwrap_or(end) + 1;
+                    member_start = pos;
+                    declaration_start = pos;
+                },
+                _ => pos += char.len_utf8(),
+            }
+        }
+        methods
+    }
+
+    /**
+     * @descript 获取字节位置所在的方法名
+     * @return 不在方法体中时为空
+     */
+    pub fn method_at(&self, offset: usize) -> Option<String> {
+        self.types().iter()
+            .flat_map(|declaration| self.methods(declaration))
+            .filter(|method| method.body.is_some_and(|(open, close)| open < offset && offset < close))
+            .max_by_key(|method| method.name_offset)
+            .map(|method| method.name)
+    }
+
+    /**
+     * @descript 查找类型中声明的方法
+     * @param type_name 类型名，不含包名
+     * @param method 方法名
+     * @return 方法声明，有重载时取第一个有方法体的声明
+     */
+    pub fn method_body(&self, type_name: &str, method: &str) -> Option<MethodDeclaration> {
+        let types = self.types();
+        let declaration = types.iter().find(|declaration| declaration.name == type_name)?;
+        self.methods(declaration).into_iter().find(|declaration| declaration.name == method && declaration.body.is_some())
+    }
+
+    /**
+     * @descript 获取注解修饰的方法
+     * @param offset 注解的位置
+     * @return 方法声明，注解不在方法上时为空
+     */
+    pub fn method_after(&self, offset: usize) -> Option<MethodDeclaration> {
+        self.types().iter()
+            .flat_map(|declaration| self.methods(declaration))
+            .find(|method| method.start <= offset && offset < method.name_offset)
+    }
+
+    /**
+     * @descript 获取方法的参数
+     * @param params_open 参数列表起始括号的位置
+     */
+    pub fn parameters(&self, params_open: usize) -> Vec<Parameter> {
+        self.arguments(params_open).into_iter()
+            .filter_map(|(start, end)| {
+                let annotations = self.annotations(start, end);
+                let declaration_end = annotations.last().map(|annotation| annotation.end).unwrap_or(start);
+                let declaration = self.code[declaration_end..end].trim().trim_start_matches("final").trim();
+                let name = Self::trailing_word(declaration)?;
+                let type_name = declaration[..declaration.len() - name.len()].split_whitespace().collect::<Vec<&str>>().join(" ");
+                Some(Parameter { annotations, type_name, name })
+            })
+            .collect()
+    }
+
+    /**
+     * @descript 获取范围内的注解
+     * @param start 起始字节位置
+     * @param end 结束字节位置
+     */
+    pub fn annotations(&self, start: usize, end: usize) -> Vec<Annotation> {
+        let mut annotations = vec![];
+        let mut pos = start;
+        while let Some(index) = self.code[pos..end].find('@') {
+            let name_start = pos + index + 1;
+            let name: String = self.code[name_start..end].chars().take_while(|char| char.is_alphanumeric() || *char == '_' || *char == '.').collect();
+            pos = name_start + name.len();
+            let rest = &self.code[pos..end];
+            let mut arguments = None;
+            if rest.trim_start().starts_with('(') {
+                let open = pos + rest.len() - rest.trim_start().len();
+                arguments = Some(open);
+                pos = self.matching_close(open).map(|close| close + 1).unwrap_or(end).min(end);
+            }
+            // @interface是注解类型的声明
+            if !name.is_empty() && name != "interface" {
+                annotations.push(Annotation {
+                    name: name.rsplit('.').next().unwrap_or_default().to_string(),
+                    start: name_start - 1,
+                    end: pos,
+                    arguments,
+                });
+            }
+        }
+        annotations
+    }
+
+    /**
+     * @descript 获取注解中的属性，没有属性名的参数为value
+     * @param annotation 注解
+     * @param attribute 属性名
+     * @return 属性值的起止字节位置
+     */
+    pub fn annotation_attribute(&self, annotation: &Annotation, attribute: &str) -> Option<(usize, usize)> {
+        let open = annotation.arguments?;
+        self.arguments(open).into_iter().find_map(|(start, end)| {
+            let argument = &self.code[start..end];
+            match Self::assignment_operator(argument) {
+                Some(index) if argument[..index].trim() == attribute => {
+                    let value = &argument[index + 1..];
+                    Some((end - value.trim_start().len(), end))
+                },
+                None if attribute == "value" => Some((start, end)),
+                _ => None,
+            }
+        })
+    }
+
+    /**
+     * @descript 查找完整出现的单词，前后不是标识符的字符
+     * @return 单词的起始字节位置
+     */
+    pub fn words(&self, word: &str) -> Vec<usize> {
+        let is_ident = |char: char| char.is_alphanumeric() || char == '_' || char == '$';
+        self.code.match_indices(word)
+            .map(|(start, _)| start)
+            .filter(|start| {
+                !self.code[..*start].ends_with(is_ident) && !self.code[start + word.len()..].starts_with(is_ident)
+            })
+            .collect()
+    }
+
+    /**
+     * @descript 获取范围内字符串字面量的内容，多个字面量直接连接
+     */
+    pub fn literal_text(&self, start: usize, end: usize) -> String {
+        let mut text = String::new();
+        let mut in_string = false;
+        for (offset, char) in self.code[start..end].char_indices() {
+            match char {
+                '"' => in_string = !in_string,
+                _ if in_string => text.push_str(&self.source[start + offset..start + offset + char.len_utf8()]),
+                _ => {},
+            }
+        }
+        text
+    }
+
+    /**
+     * @descript 获取范围内每个字符串字面量的内容
+     */
+    pub fn literals(&self, start: usize, end: usize) -> Vec<String> {
+        let mut literals = vec![];
+        let mut literal_start = None;
+        for (offset, char) in self.code[start..end].char_indices() {
+            if char != '"' {
+                continue;
+            }
+            match literal_start.take() {
+                Some(literal_start) => literals.push(self.source[literal_start..start + offset].to_string()),
+                None => literal_start = Some(start + offset + 1),
+            }
+        }
+        literals
+    }
+
+    /**
+     * @descript 获取源码中全大写命名的String常量
+     * @return 常量名和初始值的起止字节位置
+     */
+    pub fn string_constants(&self) -> Vec<(String, usize, usize)> {
+        let mut constants = vec![];
+        for start in self.words("String") {
+            let rest = &self.code[start + "String".len()..];
+            let name_start = start + "String".len() + (rest.len() - rest.trim_start().len());
+            let name: String = self.code[name_start..].chars().take_while(|char| char.is_alphanumeric() || *char == '_').collect();
+            if name.is_empty() || !name.chars().all(|char| char.is_ascii_uppercase() || char.is_ascii_digit() || char == '_') {
+                continue;
+            }
+            let value = &self.code[name_start + name.len()..];
+            let value_start = name_start + name.len() + (value.len() - value.trim_start().len());
+            if !self.code[value_start..].starts_with('=') || self.code[value_start..].starts_with("==") {
+                continue;
+            }
+            constants.push((name, value_start + 1, self.statement_end(value_start + 1)));
+        }
+        constants
+    }
+
+    /**
+     * @descript 计算字符串表达式的值，数组中的每个元素为一个值，找不到的常量保留为${常量名}
+     * @param start 表达式的起始字节位置
+     * @param end 表达式的结束字节位置
+     * @param constants 常量名和值，键为类名.常量名，如ApiPath.USER，项目中只有一个类声明的常量也可以只用常量名作为键
+     */
+    pub fn string_values(&self, start: usize, end: usize, constants: &HashMap<String, String>) -> Vec<String> {
+        let expression = self.code[start..end].trim();
+        let (start, end) = match (expression.starts_with('{'), self.code[start..end].rfind('}')) {
+            (true, Some(close)) => (start + self.code[start..end].find('{').unwrap_or_default() + 1, start + close),
+            _ => (start, end),
+        };
+        self.split_top_level(start, end, ',').into_iter()
+            .map(|(start, end)| {
+                self.split_top_level(start, end, '+').into_iter()
+                    .map(|(start, end)| match self.code[start..end].starts_with('"') {
+                        true => self.literal_text(start, end),
+                        false => {
+                            let name: String = self.code[start..end].split_whitespace().collect();
+                            self.constant_value(&name, start, constants).unwrap_or(format!("${{{}}}", name))
+                        },
+                    })
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    /**
+     * @descript 查找引用的常量的值，带类名的常量只在该类中查找
+     * 不带类名的常量依次在所在的类型和外层类型、继承和实现的类型、静态导入的类型中查找，都找不到时才使用项目中唯一的同名常量
+     * @param name 常量名，可以带类名和包名
+     * @param offset 引用常量的位置
+     * @param constants 常量名和值
+     */
+    fn constant_value(&self, name: &str, offset: usize, constants: &HashMap<String, String>) -> Option<String> {
+        if let Some((qualifier, member)) = name.rsplit_once('.') {
+            let type_name = qualifier.rsplit('.').next().unwrap_or(qualifier);
+            return constants.get(&format!("{}.{}", type_name, member)).cloned();
+        }
+        let types = self.types();
+        // 内层类型排在外层类型之前，类上的注解也属于该类型
+        let enclosing: Vec<&TypeDeclaration> = types.iter().rev()
+            .filter(|declaration| declaration.start <= offset && offset < declaration.body_close)
+            .collect();
+        let static_imports = self.static_imports();
+        let imported = static_imports.iter().filter(|(_, member)| member.as_deref() == Some(name))
+            .chain(static_imports.iter().filter(|(_, member)| member.is_none()))
+            .map(|(type_name, _)| type_name.as_str());
+        enclosing.iter().map(|declaration| declaration.name.as_str())
+            .chain(enclosing.iter().flat_map(|declaration| declaration.supertypes.iter().map(String::as_str)))
+            .chain(imported)
+            .find_map(|type_name| constants.get(&format!("{}.{}", type_name, name)))
+            .or_else(|| constants.get(name))
+            .cloned()
+    }
+
+    // 获取源码中的表达式，连续的空白合并为一个空格
+    fn expression(&self, start: usize, end: usize) -> String {
+        self.source[start..end].split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    // 获取声明的起始位置，包含前面的注解和修饰符
+    fn declaration_start(&self, offset: usize) -> usize {
+        let mut depth = 0;
+        for (index, char) in self.code[..offset].char_indices().rev() {
+            match char {
+                ')' => depth += 1,
+                '(' => depth -= 1,
+                ';' | '{' | '}' if depth == 0 => return index + 1,
+                _ => {},
+            }
+        }
+        0
+    }
+
+    // 去掉类型中的泛型参数
+    fn strip_generics(text: &str) -> String {
+        let mut depth = 0;
+        text.chars()
+            .filter(|char| {
+                match char {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    _ => return depth == 0,
+                }
+                false
+            })
+            .collect()
+    }
+
+    // 获取文本末尾的标识符
+    fn trailing_word(text: &str) -> Option<String> {
+        let word: String = text.trim_end().chars().rev().take_while(|char| char.is_alphanumeric() || *char == '_').collect();
+        match word.is_empty() {
+            true => None,
+            false => Some(word.chars().rev().collect()),
+        }
+    }
+
+    // 查找语句中最外层的赋值号，+=视为赋值，排除==、!=、<=、>=等比较运算符和其他复合运算符
+    fn assignment_operator(segment: &str) -> Option<usize> {
+        let mut depth = 0;
+        let bytes = segment.as_bytes();
+        for (index, char) in segment.char_indices() {
+            match char {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                '=' if depth == 0 => {
+                    let previous = if index > 0 { bytes[index - 1] } else { b' ' };
+                    let next = bytes.get(index + 1).copied().unwrap_or(b' ');
+                    if next != b'=' && !b"=!<>-*/%&|^".contains(&previous) {
+                        return Some(index);
+                    }
+                },
+                _ => {},
+            }
+        }
+        None
+    }
+
+    // 查找最外层拼接字符串的调用String.format(、.append(和new StringBuilder(，返回参数列表起始括号的位置
+    fn builder_calls(part: &str) -> Vec<usize> {
+        let mut opens = vec![];
+        let mut depth = 0;
+        for (offset, char) in part.char_indices() {
+            match char {
+                '(' => {
+                    if depth == 0 {
+                        let before = part[..offset].trim_end();
+                        let name_start = before.rfind(|char: char| !(char.is_alphanumeric() || char == '_')).map(|index| index + 1).unwrap_or_default();
+                        let rest = before[..name_start].trim_end();
+                        let is_builder = match &before[name_start..] {
+                            "append" => rest.ends_with('.'),
+                            "format" => rest.strip_suffix('.').is_some_and(|rest| rest.trim_end().ends_with("String")),
+                            "StringBuilder" | "StringBuffer" => rest.ends_with("new"),
+                            _ => false,
+                        };
+                        if is_builder {
+                            opens.push(offset);
+                        }
+                    }
+                    depth += 1;
+                },
+                '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {},
+            }
+        }
+        opens
+    }
+
+    // 按最外层的分隔符切分，返回去掉两侧空白后的起止位置
+    fn split_top_level(&self, start: usize, end: usize, separator: char) -> Vec<(usize, usize)> {
+        let mut ranges = vec![];
+        let mut depth = 0;
+        let mut part_start = start;
+        for (offset, char) in self.code[start..end].char_indices() {
+            match char {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ if char == separator && depth == 0 => {
+                    ranges.push((part_start, start + offset));
+                    part_start = start + offset + 1;
+                },
+                _ => {},
+            }
+        }
+        ranges.push((part_start, end));
+        ranges.into_iter()
+            .map(|(part_start, part_end)| {
+                let part = &self.code[part_start..part_end];
+                let trimmed_start = part_start + (part.len() - part.trim_start().len());
+                (trimmed_start, (part_start + part.trim_end().len()).max(trimmed_start))
+            })
+            .filter(|(part_start, part_end)| part_end > part_start)
+            .collect()
+    }
+
+    // 字面量、数字和全大写的常量不会带入外部输入
+    fn is_constant(part: &str) -> bool {
+        let part = part.trim();
+        let name = part.rsplit('.').next().unwrap_or(part);
+        part.chars().all(|char| char == '"' || char == '\'' || char.is_whitespace())
+            || part.parse::<f64>().is_ok()
+            || (name.starts_with(|char: char| char.is_ascii_uppercase()) && name.chars().all(|char| char.is_ascii_uppercase() || char.is_ascii_digit() || char == '_'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> JavaSource {
+        JavaSource::parse("Test.java", source.to_string())
+    }
+
+    #[test]
+    fn masks_comments() {
+        let source = parse("a(); // exec(cmd)\n/* run(x) */ b();\n");
+        assert_eq!(source.code, format!("a();{}\n{}b();\n", " ".repeat(" // exec(cmd)".len()), " ".repeat("/* run(x) */ ".len())));
+        assert!(source.words("exec").is_empty());
+        assert!(source.words("run").is_empty());
+        assert_eq!(source.words("b"), vec![source.source.find("b()").unwrap()]);
+    }
+
+    #[test]
+    fn masks_string_content() {
+        let source = parse("call(\"a; b(c)\", 'x', d);");
+        assert_eq!(source.code, format!("call(\"{}\", ' ', d);", " ".repeat("a; b(c)".len())));
+        assert_eq!(source.literal_text(0, source.code.len()), "a; b(c)");
+        assert_eq!(source.arguments(4).len(), 3);
+    }
+
+    #[test]
+    fn masks_escaped_quote() {
+        let source = parse(r#"s("a\"b", c);"#);
+        assert_eq!(source.code, r#"s("    ", c);"#);
+        assert_eq!(source.literals(0, source.code.len()), vec![r#"a\"b"#.to_string()]);
+    }
+
+    #[test]
+    fn masks_text_block() {
+        let source = parse("String s = \"\"\"\n  select * from t where id = ${id}\n  \"\"\";\nrun();\n");
+        assert!(!source.code.contains("select"));
+        assert_eq!(source.code.len(), source.source.len());
+        assert_eq!(source.line_of(source.code.find("run").unwrap()), 4);
+    }
+
+    #[test]
+    fn multi_byte_comment_keeps_byte_alignment() {
+        let text = "// 中文注释 exec()\nclass 用户 {\n    /* 块注释：执行\n       命令 */\n    void run() { exec(cmd); }\n}\n";
+        let source = parse(text);
+        assert_eq!(source.code.len(), source.source.len());
+        for (line, (code, raw)) in source.code.lines().zip(source.source.lines()).enumerate() {
+            assert_eq!(code.len(), raw.len(), "第{}行长度不一致", line + 1);
+        }
+        let exec = source.words("exec");
+        assert_eq!(exec, vec![text.find("exec(cmd)").unwrap()]);
+        assert_eq!(source.line_of(exec[0]), 5);
+        assert_eq!(&source.code[source.code.find("用户").unwrap()..][.."用户".len()], "用户");
+        let open = source.code.find('{').unwrap();
+        assert_eq!(source.matching_close(open), Some(text.rfind('}').unwrap()));
+    }
+}
@@ -0,0 +1,351 @@
+use std::path::Path;
+
+use crate::enums::{LangType, SpanKind};
+
+/**
+ * @Struct StringSyntax 字符串字面量的定界符
+ * @Field open 起始定界符
+ * @Field close 结束定界符
+ * @Field escape 是否支持反斜杠转义
+ * @Field multiline 是否可以跨行
+ */
+struct StringSyntax {
+    open: &'static str,
+    close: &'static str,
+    escape: bool,
+    multiline: bool,
+}
+
+/**
+ * @Struct Syntax 一种源码的注释和字符串语法
+ * @Field line_comments 行注释起始符
+ * @Field block_comments 块注释的起始符和结束符
+ * @Field strings 字符串定界符，较长的定界符需要排在前面
+ * @Field embedded 嵌入在页面中的代码块起始符和结束符，如php的<?php ?>和jsp的<% %>，代码块外视为页面内容
+ */
+struct Syntax {
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+    strings: &'static [StringSyntax],
+    embedded: Option<(&'static [&'static str], &'static str)>,
+}
+
+const JAVA: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    strings: &[
+        StringSyntax { open: "\"\"\"", close: "\"\"\"", escape: true, multiline: true },
+        StringSyntax { open: "\"", close: "\"", escape: true, multiline: false },
+        StringSyntax { open: "'", close: "'", escape: true, multiline: false },
+    ],
+    embedded: None,
+};
+
+const JSP: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[("<%--", "--%>"), ("<!--", "-->"), ("/*", "*/")],
+    strings: &[
+        StringSyntax { open: "\"", close: "\"", escape: true, multiline: false },
+        StringSyntax { open: "'", close: "'", escape: true, multiline: false },
+    ],
+    embedded: Some((&["<%"], "%>")),
+};
+
+const PHP: Syntax = Syntax {
+    line_comments: &["//", "#"],
+    block_comments: &[("<!--", "-->"), ("/*", "*/")],
+    strings: &[
+        StringSyntax { open: "\"", close: "\"", escape: true, multiline: true },
+        StringSyntax { open: "'", close: "'", escape: true, multiline: true },
+        StringSyntax { open: "`", close: "`", escape: true, multiline: true },
+    ],
+    embedded: Some((&["<?php", "<?="], "?>")),
+};
+
+const GO: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    strings: &[
+        StringSyntax { open: "\"", close: "\"", escape: true, multiline: false },
+        StringSyntax { open: "'", close: "'", escape: true, multiline: false },
+        StringSyntax { open: "`", close: "`", escape: false, multiline: true },
+    ],
+    embedded: None,
+};
+
+// xml/html等纯页面文件只区分注释
+const MARKUP: Syntax = Syntax {
+    line_comments: &[],
+    block_comments: &[("<!--", "-->")],
+    strings: &[],
+    embedded: Some((&[], "")),
+};
+
+/**
+ * 词法分析的当前状态
+ * @Enum Markup 嵌入式代码块之外的页面内容
+ * @Enum Code 代码
+ * @Enum BlockComment 块注释中，记录块注释下标和注释结束后是否回到页面内容
+ * @Enum Str 字符串中，记录字符串定界符下标
+ */
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Markup,
+    Code,
+    BlockComment(usize, bool),
+    Str(usize),
+}
+
+/**
+ * @Struct LineSpans 一行源码中各区域的划分
+ * @Field spans 每个区域的起始字节位置和区域类型，按位置排序
+ */
+#[derive(Debug, Default)]
+pub struct LineSpans {
+    spans: Vec<(usize, SpanKind)>,
+}
+
+impl LineSpans {
+
+    /**
+     * @descript 获取指定字节位置所在的区域类型
+     * @param offset 字节位置
+     */
+    pub fn kind_at(&self, offset: usize) -> SpanKind {
+        let index = self.spans.partition_point(|(start, _)| *start <= offset);
+        match index {
+            0 => SpanKind::Code,
+            _ => self.spans[index - 1].1,
+        }
+    }
+
+    fn push(&mut self, start: usize, kind: SpanKind) {
+        match self.spans.last_mut() {
+            Some((_, last_kind)) if *last_kind == kind => {},
+            Some((last_start, last_kind)) if *last_start == start => *last_kind = kind,
+            _ => self.spans.push((start, kind)),
+        }
+    }
+}
+
+/**
+ * @Struct Lexer 轻量的逐行词法分析器，只区分代码、注释和字符串，块注释和多行字符串的状态会跨行保留
+ * @Field syntax 使用的语法
+ * @Field state 上一行结束时的状态
+ */
+pub struct Lexer {
+    syntax: &'static Syntax,
+    state: State,
+}
+
+impl Lexer {
+
+    /**
+     * @descript 根据语言类型和文件后缀选择语法
+     * @param lang_type 扫描的语言类型
+     * @param file_path 文件路径
+     */
+    pub fn for_file(lang_type: &LangType, file_path: &str) -> Self {
+        let ext = Path::new(file_path).extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let syntax = match (ext.as_str(), lang_type) {
+            ("jsp" | "jspx" | "jspf", _) => &JSP,
+            ("xml" | "html" | "htm" | "vue", _) => &MARKUP,
+            ("php" | "phtml", _) | (_, LangType::PHP) => &PHP,
+            ("go", _) | (_, LangType::GO) => &GO,
+            // java和其他注册的语言按类C语法处理
+            _ => &JAVA,
+        };
+        let state = if syntax.embedded.is_some() { State::Markup } else { State::Code };
+        Lexer { syntax, state }
+    }
+
+    /**
+     * @descript 分析下一行源码
+     * @param line 源码行，不包含换行符
+     * @return 该行各区域的划分
+     */
+    pub fn next_line(&mut self, line: &str) -> LineSpans {
+        let syntax = self.syntax;
+        let bytes = line.as_bytes();
+        let mut spans = LineSpans::default();
+        // 单行字符串未闭合时不延续到下一行
+        if let State::Str(index) = self.state {
+            if !syntax.strings[index].multiline {
+                self.state = State::Code;
+            }
+        }
+        spans.push(0, Self::kind_of(self.state));
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let rest = &line[pos..];
+            match self.state {
+                State::Markup => {
+                    if let Some((index, (open, _))) = syntax.block_comments.iter().enumerate().find(|(_, (open, _))| rest.starts_with(open)) {
+                        self.state = State::BlockComment(index, true);
+                        spans.push(pos, SpanKind::Comment);
+                        pos += open.len();
+                    } else if let Some(open) = syntax.embedded.and_then(|(opens, _)| opens.iter().find(|open| rest.starts_with(*open))) {
+                        self.state = State::Code;
+                        pos += open.len();
+                    } else {
+                        pos += Self::char_len(rest);
+                    }
+                },
+                State::Code => {
+                    if let Some((index, (open, _))) = syntax.block_comments.iter().enumerate().find(|(_, (open, _))| rest.starts_with(open)) {
+                        self.state = State::BlockComment(index, false);
+                        spans.push(pos, SpanKind::Comment);
+                        pos += open.len();
+                    } else if syntax.line_comments.iter().any(|comment| rest.starts_with(comment)) && !rest.starts_with("#[") {
+                        spans.push(pos, SpanKind::Comment);
+                        // php的行注释在?>处结束
+                        match syntax.embedded.map(|(_, close)| close).filter(|close| !close.is_empty()).and_then(|close| rest.find(close)) {
+                            Some(offset) => {
+                                pos += offset;
+                                spans.push(pos, SpanKind::Code);
+                            },
+                            None => pos = bytes.len(),
+                        }
+                    } else if let Some(index) = syntax.strings.iter().position(|string| rest.starts_with(string.open)) {
+                        self.state = State::Str(index);
+                        spans.push(pos, SpanKind::String);
+                        pos += syntax.strings[index].open.len();
+                    } else if let Some(close) = syntax.embedded.map(|(_, close)| close).filter(|close| !close.is_empty() && rest.starts_with(*close)) {
+                        self.state = State::Markup;
+                        pos += close.len();
+                    } else {
+                        pos += Self::char_len(rest);
+                    }
+                },
+                State::BlockComment(index, in_markup) => {
+                    let close = syntax.block_comments[index].1;
+                    match rest.find(close) {
+                        Some(offset) => {
+                            pos += offset + close.len();
+                            self.state = if in_markup { State::Markup } else { State::Code };
+                            spans.push(pos, SpanKind::Code);
+                        },
+                        None => pos = bytes.len(),
+                    }
+                },
+                State::Str(index) => {
+                    let string = &syntax.strings[index];
+                    if string.escape && bytes[pos] == b'\\' {
+                        pos += 1 + Self::char_len(&line[pos + 1..]);
+                    } else if rest.starts_with(string.close) {
+                        pos += string.close.len();
+                        self.state = State::Code;
+                        spans.push(pos, SpanKind::Code);
+                    } else {
+                        pos += Self::char_len(rest);
+                    }
+                },
+            }
+        }
+        spans
+    }
+
+    // 按字符前进，避免落在多字节字符的中间
+    fn char_len(rest: &str) -> usize {
+        rest.chars().next().map(char::len_utf8).unwrap_or(1)
+    }
+
+    fn kind_of(state: State) -> SpanKind {
+        match state {
+            State::Markup | State::Code => SpanKind::Code,
+            State::BlockComment(_, _) => SpanKind::Comment,
+            State::Str(_) => SpanKind::String,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn java() -> Lexer {
+        Lexer::for_file(&LangType::JAVA, "Test.java")
+    }
+
+    #[test]
+    fn line_comment() {
+        let line = "int a = 1; // exec(\"x\")";
+        let spans = java().next_line(line);
+        let comment = line.find("//").unwrap();
+        assert_eq!(spans.kind_at(0), SpanKind::Code);
+        assert_eq!(spans.kind_at(comment - 1), SpanKind::Code);
+        assert_eq!(spans.kind_at(comment), SpanKind::Comment);
+        assert_eq!(spans.kind_at(line.find("exec").unwrap()), SpanKind::Comment);
+    }
+
+    #[test]
+    fn block_comment_across_lines() {
+        let mut lexer = java();
+        let first = "a(); /* start";
+        let spans = lexer.next_line(first);
+        assert_eq!(spans.kind_at(0), SpanKind::Code);
+        assert_eq!(spans.kind_at(first.find("/*").unwrap()), SpanKind::Comment);
+
+        let spans = lexer.next_line("  exec(cmd);");
+        assert_eq!(spans.kind_at(2), SpanKind::Comment);
+
+        let last = "end */ b();";
+        let spans = lexer.next_line(last);
+        assert_eq!(spans.kind_at(0), SpanKind::Comment);
+        assert_eq!(spans.kind_at(last.find("*/").unwrap() + 1), SpanKind::Comment);
+        assert_eq!(spans.kind_at(last.find('b').unwrap()), SpanKind::Code);
+    }
+
+    #[test]
+    fn string_hides_comment_marker() {
+        let line = "call(\"a // b /* c\", x);";
+        let spans = java().next_line(line);
+        assert_eq!(spans.kind_at(line.find('"').unwrap()), SpanKind::String);
+        assert_eq!(spans.kind_at(line.find("//").unwrap()), SpanKind::String);
+        assert_eq!(spans.kind_at(line.find("/*").unwrap()), SpanKind::String);
+        assert_eq!(spans.kind_at(line.find('x').unwrap()), SpanKind::Code);
+    }
+
+    #[test]
+    fn escaped_quote() {
+        let line = r#"s = "a\"b" + c;"#;
+        let spans = java().next_line(line);
+        assert_eq!(spans.kind_at(line.find('b').unwrap()), SpanKind::String);
+        assert_eq!(spans.kind_at(line.find('+').unwrap()), SpanKind::Code);
+        assert_eq!(spans.kind_at(line.find('c').unwrap()), SpanKind::Code);
+    }
+
+    #[test]
+    fn unclosed_string_ends_at_line() {
+        let mut lexer = java();
+        let spans = lexer.next_line("s = \"abc");
+        assert_eq!(spans.kind_at(6), SpanKind::String);
+        let spans = lexer.next_line("exec(s);");
+        assert_eq!(spans.kind_at(0), SpanKind::Code);
+    }
+
+    #[test]
+    fn text_block() {
+        let mut lexer = java();
+        let first = "String sql = \"\"\"";
+        let spans = lexer.next_line(first);
+        assert_eq!(spans.kind_at(first.find('"').unwrap()), SpanKind::String);
+
+        let spans = lexer.next_line("  select * /* not comment */ from t");
+        assert_eq!(spans.kind_at(2), SpanKind::String);
+        assert_eq!(spans.kind_at(11), SpanKind::String);
+
+        let last = "  \"\"\"; run();";
+        let spans = lexer.next_line(last);
+        assert_eq!(spans.kind_at(2), SpanKind::String);
+        assert_eq!(spans.kind_at(last.find("run").unwrap()), SpanKind::Code);
+    }
+
+    #[test]
+    fn multi_byte_comment() {
+        let line = "a(); // 中文注释 exec";
+        let spans = java().next_line(line);
+        assert_eq!(spans.kind_at(line.find("中").unwrap()), SpanKind::Comment);
+        assert_eq!(spans.kind_at(line.find("exec").unwrap()), SpanKind::Comment);
+    }
+}
@@ -1,4 +1,9 @@
 pub mod rule_check;
+pub mod rule_matcher;
+pub mod rule_lint;
+pub mod lexer;
+pub mod java_source;
+pub mod suppression;
 pub mod path_util;
 pub mod file_util;
 
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use super::PathUtil;
 
 impl PathUtil {
@@ -7,4 +9,18 @@ impl PathUtil {
             .map(|item| item.replace(prefix, ""))
             .collect()
     }
-}
\ No newline at end of file
+
+    /**
+     * @descript 获取文件相对于项目根目录的路径，统一使用/分隔
+     * @param path 文件路径
+     * @param root 项目根目录
+     * @return 相对路径，不在根目录下时返回原路径
+     */
+    pub fn relative_path(path: &str, root: &str) -> String {
+        let relative = match Path::new(path).strip_prefix(root) {
+            Ok(relative) => relative.to_string_lossy().to_string(),
+            Err(_) => path.to_string(),
+        };
+        relative.replace('\\', "/")
+    }
+}
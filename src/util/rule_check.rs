@@ -1,32 +1,44 @@
-use std::{fs::File, io::{BufReader, BufRead, Write}, error::Error, sync::{mpsc::{channel, Sender}, Arc, Mutex}};
+use std::{any::Any, collections::HashSet, fs, io, error::Error, panic::{self, AssertUnwindSafe}, sync::{mpsc::{channel, Sender}, Arc, Mutex}};
 
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
-use regex::Regex;
-use crate::{model::rule::Rule, enums::RuleCondition};
-use lazy_static::lazy_static;
+use crate::{enums::{CommentHits, ScanStage, Severity, SpanKind}, error::ScanError, model::{baseline::Baseline, config::Config, file_error::FileError, finding::{Finding, SourceLine}, result::ScanResult, rule::Rule}};
+
+use super::{lexer::{Lexer, LineSpans}, rule_matcher::RuleMatcher, suppression::SuppressionMarker, PathUtil};
 
 pub struct RuleCheck;
 
-// 统计总行数
-lazy_static! {
-    // pub static ref TOTAL_LINE: Arc<Mutex<usize>> = Arc::new(Mutex::new(0));
-    pub static ref TOTAL_LINE: Arc<Mutex<usize>> = Arc::new(Mutex::new(0));
-    // 统计有效行数(除去空行)
-    pub static ref NOT_NULL_LINE: Arc<Mutex<usize>> = Arc::new(Mutex::new(0));
+/**
+ * @Struct CheckedFile 单个文件的检查结果
+ * @Field findings 命中的风险代码
+ * @Field errors 处理失败的记录
+ * @Field warnings 不影响检查结果的警告，如跳过的非utf8编码的行
+ * @Field total_line 文件总行数
+ * @Field not_null_line 文件非空行数
+ */
+#[derive(Default)]
+struct CheckedFile {
+    findings: Vec<Finding>,
+    errors: Vec<FileError>,
+    warnings: Vec<FileError>,
+    total_line: usize,
+    not_null_line: usize,
 }
 
 impl RuleCheck{
 
     /**
-     * @descript 获取命中规则的代码列表
-     * @param rules 规则列表
+     * @descript 获取命中规则的代码列表，源码注释中忽略的风险代码单独列出
+     * @param config 配置信息，提供规则、上下文行数和基线
      * @param path_list 要检查的源码文件路径列表
-     * @param report_file 报告文件
-     * @return 可能存在的错误
+     * @param result 扫描结果，填充按文件路径和行号排序的命中结果、基线对比结果和行数统计，处理失败的文件记录到扫描错误中
      */
-    pub fn start(rules: &Vec<Rule>, path_list: Vec<String>, report_file: &mut File, use_ui: bool) -> Result<(), Box<dyn Error>> {
-        report_file.write("### 根据规则扫描出的风险代码:   \n\n```\n".as_bytes())?;
+    pub fn start(config: &Config, path_list: Vec<String>, result: &mut ScanResult) -> Result<(), Box<dyn Error>> {
+        let rules = &config.rule;
+        let use_ui = config.use_ui;
+        // 规则只编译一次，非法的正则在扫描开始前就会报错
+        let matcher = RuleMatcher::new(rules)?;
+        let rule_ids: HashSet<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
         // 进度条计算
         let total_files = path_list.len() as u64;
         let progress_bar = ProgressBar::new(total_files);
@@ -39,13 +51,20 @@ impl RuleCheck{
         // 使用 rayon 的 par_iter 来并发地处理文件列表
         path_list.par_iter()
             .for_each(|file_path| {
-                // 克隆 Arc<Mutex<Sender<String>>> 以供每个线程使用
+                // 克隆 Arc<Mutex<Sender<CheckedFile>>> 以供每个线程使用
                 let tx_shared_clone = Arc::clone(&tx_shared);
                 progress_bar.inc(1);
                 if use_ui {
-                    println!("{}/{}", progress_bar.position(), progress_bar.length().unwrap());
+                    println!("{}/{}", progress_bar.position(), progress_bar.length().unwrap_or_default());
                 }
-                Self::check_file(rules, file_path, tx_shared_clone);
+                // 单个文件读取失败或出现panic时记录错误后继续扫描其他文件
+                let checked = panic::catch_unwind(AssertUnwindSafe(|| Self::check_file(config, &matcher, &rule_ids, file_path)));
+                let checked = match checked {
+                    Ok(Ok(checked)) => checked,
+                    Ok(Err(err)) => CheckedFile { errors: vec![FileError::new(ScanStage::Rules, file_path, &err)], ..CheckedFile::default() },
+                    Err(panic) => CheckedFile { errors: vec![FileError::new(ScanStage::Rules, file_path, &ScanError::Internal(Self::panic_message(panic)))], ..CheckedFile::default() },
+                };
+                Self::send(&tx_shared_clone, checked);
             });
     
         progress_bar.finish();
@@ -53,111 +72,228 @@ impl RuleCheck{
         // 关闭发送端，以便接收端知道不会再有更多的数据发送
         drop(tx_shared);
 
-        // 继续处理接收端接收到的数据
-        for received_data in rx {
-            report_file.write(received_data.as_bytes())?;
+        // 继续处理接收端接收到的数据，每次接收的是同一个文件的全部命中结果、处理失败的记录和行数
+        let mut all_findings: Vec<Finding> = vec![];
+        for checked in rx {
+            all_findings.extend(checked.findings);
+            result.errors.extend(checked.errors);
+            result.warnings.extend(checked.warnings);
+            result.total_line += checked.total_line;
+            result.not_null_line += checked.not_null_line;
+        }
+        all_findings.sort_by(|a, b| {
+            a.file_path.cmp(&b.file_path)
+                .then(a.line.cmp(&b.line))
+                .then(a.end_line.cmp(&b.end_line))
+                .then(b.severity.cmp(&a.severity))
+                .then(a.rule_id.cmp(&b.rule_id))
+        });
+        for finding in all_findings.iter_mut() {
+            finding.fingerprint = Baseline::fingerprint(&finding.rule_id, &PathUtil::relative_path(&finding.file_path, &config.work_dir), &finding.code);
         }
-        report_file.write("\n```  \n\n".as_bytes())?;
+        let (suppressed, mut findings): (Vec<Finding>, Vec<Finding>) = all_findings.into_iter()
+            .partition(|finding| finding.suppression.is_some());
+        let baseline_diff = config.baseline.as_ref()
+            .map(|baseline| baseline.diff(&mut findings, config.baseline_file_path.as_deref().unwrap_or_default()));
+        result.findings = findings;
+        result.suppressed = suppressed;
+        result.baseline = baseline_diff;
         Ok(())
     }
 
     /**
-     * @descript 检查规则是否命中
-     * @param rules 规则列表
-     * @param data 要进行检查的数据
-     * @return (bool, String) 是否命中和命中的规则描述信息
+     * @descript 检查文件是否命中规则，被注释忽略的命中会记录忽略信息
+     * 规则只在其声明的源码区域(代码/注释/字符串)中命中才会记录，跨行规则和组合条件在读取完整个文件后执行
+     * @param config 配置信息，提供规则、语言类型、注释命中的处理方式和上下文行数
+     * @param matcher 规则编译后的匹配器
+     * @param rule_ids 所有规则的id，用于解析忽略标记
+     * @param file_path 要进行检查的文件
+     * @return 命中结果和不影响扫描的警告，如非utf8编码的行，文件无法读取时返回错误
      */
-    fn rule_match_hit(rules: &Vec<Rule>, data: &str) -> (bool, String) {
-        for rule in rules {
-            match rule.condition {
-                RuleCondition::Contain => {
-                    if data.contains(&rule.keyword) {
-                        // println!("hit {:?}", rule.keyword);
-                        return (true, rule.note.clone())
-                    }
-                }
-                RuleCondition::Regex => {
-                    // 可能存在正则错误的情况
-                    let regex = match Regex::new(&rule.keyword) {
-                        Ok(re) => re,
-                        Err(_) => panic!("请检查正则: [{}]是否是合法的表达式", rule.keyword),
-                    };
-                    if regex.is_match(data) {
-                        // println!("hit {:?}", rule.keyword);
-                        return (true, rule.note.clone())
-                    }
-                }
-            }
+    fn check_file(config: &Config, matcher: &RuleMatcher, rule_ids: &HashSet<&str>, file_path: &str) -> Result<CheckedFile, ScanError> {
+        let rules = &config.rule;
+        // 根据框架、后缀和路径过滤出适用于该文件的规则
+        let relative_path = PathUtil::relative_path(file_path, &config.work_dir);
+        let applicable: Vec<bool> = rules.iter().enumerate()
+            .map(|(index, rule)| rule.applies_to_project(&config.framework, &config.orm) && matcher.applies_to_file(index, &relative_path))
+            .collect();
+        let content = fs::read(file_path).map_err(|err| ScanError::io(file_path, err))?;
+        // 非utf8编码的行跳过检查，文件的其他行仍然检查，记录行数后在扫描警告中提示
+        let mut invalid_lines = 0;
+        let lines = content.split_inclusive(|byte| *byte == b'\n').map(|line| {
+            let line = line.strip_suffix(b"\n").unwrap_or(line);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            String::from_utf8(line.to_vec()).map_err(|err| {
+                invalid_lines += 1;
+                io::Error::new(io::ErrorKind::InvalidData, err)
+            })
+        });
+        let (findings, total_line, not_null_line) = Self::check_source(config, rules, matcher, &applicable, rule_ids, file_path, lines);
+        let mut warnings: Vec<FileError> = vec![];
+        if invalid_lines > 0 {
+            warnings.push(FileError::warning(ScanStage::Rules, file_path, &format!("共{}行不是utf8编码,已跳过这些行", invalid_lines)));
+        }
+        Ok(CheckedFile { findings, warnings, total_line, not_null_line, ..CheckedFile::default() })
+    }
+
+    // 发送一个文件的检查结果
+    fn send(sender: &Arc<Mutex<Sender<CheckedFile>>>, checked: CheckedFile) {
+        if let Ok(sender) = sender.lock() {
+            let _ = sender.send(checked);
+        }
+    }
+
+    // 获取panic的信息
+    fn panic_message(panic: Box<dyn Any + Send>) -> String {
+        match panic.downcast::<String>() {
+            Ok(message) => *message,
+            Err(panic) => panic.downcast_ref::<&str>().map(|message| message.to_string()).unwrap_or_else(|| "未知错误".to_string()),
         }
-        return (false, String::new())
     }
 
     /**
-     * @descript 检查文件是否命中规则，并将命中的信息通过发送者传递出去
-     * @param rules 需要进行检查的规则列表
-     * @param file_path 要进行检查的文件
-     * @param sender 发送者
+     * @descript 检查一段源码是否命中规则，规则自检时也用于执行规则示例
+     * @param config 配置信息，提供语言类型、注释命中的处理方式和上下文行数
+     * @param rules 规则列表，与matcher编译时的规则一致
+     * @param matcher 规则编译后的匹配器
+     * @param applicable 规则下标对应的规则是否适用于该源码
+     * @param rule_ids 所有规则的id，用于解析忽略标记
+     * @param file_path 源码的文件路径，用于选择词法和记录命中结果
+     * @param lines 源码的全部行
+     * @return 命中结果、总行数和非空行数
      */
-    fn check_file(rules: &Vec<Rule>, file_path: &str, sender: Arc<Mutex<Sender<String>>>) {
-        let mut matching_lines: Vec<String> = Vec::new();
-        // let path = path_list.get(index).unwrap();
-        let file = File::open(&file_path).unwrap();
-        let lines = BufReader::new(file).lines();
+    pub fn check_source(config: &Config, rules: &[Rule], matcher: &RuleMatcher, applicable: &[bool], rule_ids: &HashSet<&str>, file_path: &str, lines: impl Iterator<Item = io::Result<String>>) -> (Vec<Finding>, usize, usize) {
+        let context_lines = config.context_lines;
+        let mut lexer = Lexer::for_file(&config.lang_type, file_path);
+        // 命中的规则下标和命中结果
+        let mut hits: Vec<(usize, Finding)> = Vec::new();
+        // 文件中的忽略标记
+        let mut markers: Vec<SuppressionMarker> = Vec::new();
+        // 需要上下文、存在跨行规则或组合条件时保留文件的全部行
+        let keep_lines = context_lines > 0 || matcher.has_multiline() || matcher.has_conditions();
+        let mut source_lines: Vec<String> = Vec::new();
+        let mut line_spans: Vec<LineSpans> = Vec::new();
         let mut cur_line = 0;
         let mut not_null_line = 0;
 
         lines.for_each(|line|{
             cur_line += 1;
+            if keep_lines {
+                source_lines.push(line.as_ref().map(|line| line.to_string()).unwrap_or_default());
+            }
             match line {
                 Ok(line) => {
+                    let spans = lexer.next_line(&line);
+                    // 跨行规则需要每一行的区域划分，放在本行处理完之后保存
                     let trim_line = line.trim();
-                    if trim_line.len() > 0 {
+                    if !trim_line.is_empty() {
                         not_null_line += 1;
-                        let (hit, description) = Self::rule_match_hit(&rules, &line);
-                        if hit {
-                            let format_result = format!("[-] 行[{}]命中风险代码:{:?},说明: [{}]  \n", cur_line, trim_line, description);
-                            matching_lines.push(format_result);
+                        if let Some(marker) = SuppressionMarker::parse(&line, cur_line, &spans, rule_ids) {
+                            if !marker.unknown_rule_ids.is_empty() {
+                                println!("[-]文件[{}]第{}行的忽略标记中规则[{}]不存在，不会忽略这些规则", file_path, cur_line, marker.unknown_rule_ids.join(","));
+                            }
+                            markers.push(marker);
                         }
+                        // 一行可能同时命中多条规则，每条规则都需要记录
+                        for (index, starts) in matcher.matches(&line) {
+                            if !applicable[index] {
+                                continue;
+                            }
+                            let rule = &rules[index];
+                            let kinds: Vec<SpanKind> = starts.iter().map(|start| spans.kind_at(*start)).collect();
+                            if let Some((span, severity)) = Self::hit_span(config, rule, &kinds) {
+                                hits.push((index, Self::new_finding(rule, severity, span, file_path, cur_line, cur_line, trim_line.to_string())));
+                            }
+                        }
+                    }
+                    if matcher.has_multiline() {
+                        line_spans.push(spans);
                     }
                 },
                 Err(_) => {
+                    if matcher.has_multiline() {
+                        line_spans.push(LineSpans::default());
+                    }
                     // println!("文件[{}][{}]行内容读取失败: {}", path, cur_line, err)
                 },
             }
         });
-
-        // for line in lines {
-        //     cur_line += 1;
-        //     match line {
-        //         Ok(line) => {
-        //             let trim_line = line.trim();
-        //             if trim_line.len() > 0 {
-        //                 not_null_line += 1;
-        //                 let (hit, description) = Self::rule_match_hit(&rules, &line);
-        //                 if hit {
-        //                     let format_result = format!("[-] 行[{}]命中风险代码:{:?},说明: [{}]  \n", cur_line, trim_line, description);
-        //                     matching_lines.push(format_result);
-        //                 }
-        //             }
-        //         },
-        //         Err(_) => {
-        //             // println!("文件[{}][{}]行内容读取失败: {}", path, cur_line, err)
-        //         },
-        //     }
-        // }
-        // 使用原子操作
-        {
-            *TOTAL_LINE.lock().unwrap() += cur_line;
-            *NOT_NULL_LINE.lock().unwrap() += not_null_line;
+        // 跨行规则，报告命中开始的行以及跨越的行
+        for hit in matcher.matches_multiline(&source_lines) {
+            if !applicable[hit.rule] {
+                continue;
+            }
+            let rule = &rules[hit.rule];
+            let kind = line_spans[hit.start_line].kind_at(hit.start_column);
+            if let Some((span, severity)) = Self::hit_span(config, rule, &[kind]) {
+                let code = source_lines[hit.start_line..=hit.end_line].iter().map(|line| line.trim()).collect::<Vec<&str>>().join("\n");
+                hits.push((hit.rule, Self::new_finding(rule, severity, span, file_path, hit.start_line + 1, hit.end_line + 1, code)));
+            }
+        }
+        // 组合条件需要整个文件的内容，只在有命中时拼接
+        let content = match matcher.has_conditions() && !hits.is_empty() {
+            true => source_lines.join("\n"),
+            false => String::new(),
+        };
+        let mut matching_lines: Vec<Finding> = hits.into_iter()
+            .filter(|(index, finding)| matcher.condition_holds(*index, &source_lines, &content, finding.line - 1, finding.end_line - 1))
+            .map(|(_, finding)| finding)
+            .collect();
+        // 根据忽略标记记录被忽略的命中
+        if !markers.is_empty() {
+            for finding in matching_lines.iter_mut() {
+                finding.suppression = markers.iter()
+                    .find(|marker| marker.applies_to(finding.line, &finding.rule_id))
+                    .map(|marker| marker.to_suppression());
+            }
         }
-        // 当该文件至少有一行命中了规则
-        if matching_lines.len() > 0 {
-            let mut file_out = String::new();
-            file_out.push_str(&format!("  \n  \n[!]文件[{}]找到可疑危险函数:  \n", file_path));
-            sender.lock().unwrap().send(file_out).unwrap();
-            for line in matching_lines {
-                sender.lock().unwrap().send(format!(" {}\n", line)).unwrap();
+        // 补充命中行前后的上下文
+        if context_lines > 0 {
+            for finding in matching_lines.iter_mut() {
+                let begin = finding.line.saturating_sub(context_lines + 1);
+                let end = (finding.end_line + context_lines).min(source_lines.len());
+                finding.context = source_lines[begin..end].iter().enumerate().map(|(offset, code)| SourceLine {
+                    line: begin + offset + 1,
+                    code: code.clone(),
+                }).collect();
             }
         }
+        (matching_lines, cur_line, not_null_line)
+    }
+
+    /**
+     * @descript 根据命中位置所在的源码区域判断命中是否有效
+     * @param config 配置信息，提供注释命中的处理方式
+     * @param rule 命中的规则
+     * @param kinds 规则每个命中位置所在的源码区域
+     * @return 有效时返回命中所在的区域和最终的严重程度
+     */
+    fn hit_span(config: &Config, rule: &Rule, kinds: &[SpanKind]) -> Option<(SpanKind, Severity)> {
+        match kinds.iter().find(|kind| rule.match_in.contains(kind)) {
+            Some(kind) => Some((*kind, rule.severity)),
+            // 只在注释中命中时按配置跳过或降级
+            None if config.comment_hits == CommentHits::Downgrade && kinds.contains(&SpanKind::Comment) => Some((SpanKind::Comment, Severity::Info)),
+            None => None,
+        }
+    }
+
+    /**
+     * @descript 构建规则命中结果，指纹、基线状态、忽略信息和上下文在后续步骤中补充
+     */
+    fn new_finding(rule: &Rule, severity: Severity, span: SpanKind, file_path: &str, line: usize, end_line: usize, code: String) -> Finding {
+        Finding {
+            rule_id: rule.id.clone(),
+            severity,
+            file_path: file_path.to_string(),
+            line,
+            end_line,
+            code,
+            note: rule.note.clone(),
+            span,
+            fingerprint: String::new(),
+            baseline_state: None,
+            suppression: None,
+            context: vec![],
+        }
     }
 }
\ No newline at end of file
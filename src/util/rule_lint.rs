@@ -0,0 +1,389 @@
+use std::{collections::HashSet, slice};
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{enums::RuleCondition, model::{config::Config, rule::{Rule, RuleSource}}};
+
+use super::{rule_check::RuleCheck, rule_matcher::RuleMatcher};
+
+// 正则编译后的体积上限，超过时扫描性能会明显下降
+const REGEX_SIZE_LIMIT: usize = 256 * 1024;
+// Contain关键字短于该长度时容易命中大量无关代码
+const MIN_KEYWORD_LEN: usize = 4;
+// Contain关键字中出现这些内容时很可能是误写成Contain的正则
+const REGEX_HINTS: [&str; 17] = ["\\(", "\\)", "\\.", "\\s", "\\S", "\\w", "\\W", "\\d", "\\D", "\\b", "\\$", "\\{", ".*", ".+", "{0,", "[^", "(?"];
+
+pub struct RuleLint;
+
+/**
+ * @Enum LintLevel 规则检查问题的级别
+ * @Enum Error 规则无法使用或示例未通过
+ * @Enum Warning 规则可以使用但可能存在误报、重复或性能问题
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Error,
+    Warning,
+}
+
+/**
+ * @Struct LintIssue 规则检查发现的问题
+ * @Field rule_id 存在问题的规则id
+ * @Field level 问题级别
+ * @Field message 问题说明
+ */
+#[derive(Debug)]
+pub struct LintIssue {
+
+    pub rule_id: String,
+
+    pub level: LintLevel,
+
+    pub message: String,
+
+}
+
+/**
+ * @Struct LintResult 规则检查结果
+ * @Field issues 发现的问题
+ * @Field examples 执行的示例数量
+ * @Field passed 通过的示例数量
+ */
+#[derive(Debug, Default)]
+pub struct LintResult {
+
+    pub issues: Vec<LintIssue>,
+
+    pub examples: usize,
+
+    pub passed: usize,
+
+}
+
+impl LintResult {
+
+    fn push(&mut self, rule: &Rule, level: LintLevel, message: String) {
+        self.issues.push(LintIssue {
+            rule_id: rule.id.clone(),
+            level,
+            message,
+        });
+    }
+
+    /**
+     * @descript 指定级别的问题数量
+     */
+    pub fn count(&self, level: LintLevel) -> usize {
+        self.issues.iter().filter(|issue| issue.level == level).count()
+    }
+}
+
+impl RuleLint {
+
+    /**
+     * @descript 检查已加载的全部规则，所有问题一次性返回
+     * @param config 已加载规则的配置信息，示例按配置中的语言和注释命中方式执行
+     * @param sources 去重和覆盖前的规则来源，用于定位重复的规则
+     * @param example_ext 规则未限定后缀时示例文件使用的后缀，一般为扫描器的默认后缀
+     * @return 检查结果，问题按规则顺序排列
+     */
+    pub fn lint(config: &Config, sources: &[RuleSource], example_ext: &str) -> LintResult {
+        let mut result = LintResult::default();
+        for rule in &config.rule {
+            // 单独编译每条规则，一条规则有误时不影响其他规则的检查
+            match RuleMatcher::new(slice::from_ref(rule)) {
+                Ok(matcher) => {
+                    Self::check_pattern(rule, &mut result);
+                    Self::run_examples(config, rule, &matcher, example_ext, &mut result);
+                },
+                Err(err) => result.push(rule, LintLevel::Error, err.to_string().trim_start_matches("[-]").to_string()),
+            }
+        }
+        Self::check_sources(config, sources, &mut result);
+        Self::check_overlap(&config.rule, &mut result);
+        result.issues.sort_by_key(|issue| config.rule.iter().position(|rule| rule.id == issue.rule_id));
+        result
+    }
+
+    /**
+     * @descript 检查关键字的写法，如匹配方式与关键字不符、可以匹配空字符串以及过于宽泛的关键字
+     */
+    fn check_pattern(rule: &Rule, result: &mut LintResult) {
+        let keyword = rule.keyword.as_str();
+        match rule.condition {
+            RuleCondition::Contain => {
+                if keyword.is_empty() {
+                    result.push(rule, LintLevel::Error, "关键字为空,会命中每一行".to_string());
+                    return
+                }
+                if REGEX_HINTS.iter().any(|hint| keyword.contains(hint)) {
+                    result.push(rule, LintLevel::Warning, format!("关键字[{}]看起来是正则,但匹配方式是Contain,会按字面量匹配", keyword));
+                }
+                if keyword.trim().chars().count() < MIN_KEYWORD_LEN {
+                    result.push(rule, LintLevel::Warning, format!("关键字[{}]过短,可能命中大量无关代码", keyword));
+                }
+            },
+            RuleCondition::Regex => {
+                // 规则能编译说明正则合法，这里只检查写法
+                let regex = match Regex::new(keyword) {
+                    Ok(regex) => regex,
+                    Err(_) => return,
+                };
+                if regex.is_match("") {
+                    result.push(rule, LintLevel::Error, format!("正则[{}]可以匹配空字符串,会命中每一行", keyword));
+                }
+                if RegexBuilder::new(keyword).size_limit(REGEX_SIZE_LIMIT).build().is_err() {
+                    result.push(rule, LintLevel::Warning, format!("正则[{}]编译后体积过大,扫描时性能会明显下降", keyword));
+                }
+                if regex::escape(keyword) == keyword {
+                    result.push(rule, LintLevel::Warning, format!("正则[{}]中没有特殊字符,建议改为Contain", keyword));
+                    if keyword.trim().chars().count() < MIN_KEYWORD_LEN {
+                        result.push(rule, LintLevel::Warning, format!("关键字[{}]过短,可能命中大量无关代码", keyword));
+                    }
+                }
+                if keyword.contains("()") {
+                    result.push(rule, LintLevel::Warning, format!("正则[{}]中的()是空分组,匹配括号需要写成\\(\\)", keyword));
+                }
+            },
+        }
+    }
+
+    /**
+     * @descript 检查去重和覆盖前的规则，加载时会被忽略或覆盖的重复规则只能在这里发现
+     * 同一规则文件中的id需要唯一，不同id的规则关键字相同时同一处代码会重复报告，不同文件中id相同是有意的覆盖
+     */
+    fn check_sources(config: &Config, sources: &[RuleSource], result: &mut LintResult) {
+        let located: Vec<(&RuleSource, usize, &Rule, String)> = sources.iter()
+            .flat_map(|source| source.rules.iter().enumerate().map(move |(index, rule)| (source, index, rule, Self::pattern(rule))))
+            .filter(|(_, _, rule, _)| !config.disabled_rules.contains(&rule.id))
+            .collect();
+        for (position, (source, index, rule, pattern)) in located.iter().enumerate() {
+            let earlier = &located[..position];
+            if let Some((_, first, _, _)) = earlier.iter().find(|(other_source, _, other, _)| std::ptr::eq(*other_source, *source) && other.id == rule.id) {
+                result.push(rule, LintLevel::Error, format!("规则文件[{}]中第{}条规则与第{}条规则的id相同,id需要唯一", source.name, index + 1, first + 1));
+                continue;
+            }
+            let duplicate = earlier.iter().find(|(_, _, other, other_pattern)| other.id != rule.id && other_pattern == pattern && other.scope == rule.scope);
+            if let Some((other_source, other_index, other, _)) = duplicate {
+                result.push(rule, LintLevel::Warning, format!("规则文件[{}]中第{}条规则的关键字与规则文件[{}]中第{}条规则[{}]相同,同一处代码会重复报告", source.name, index + 1, other_source.name, other_index + 1, other.id));
+            }
+        }
+    }
+
+    /**
+     * @descript 检查规则之间的包含关系，Contain关键字被另一条规则的关键字包含时，同一处代码会被重复报告
+     */
+    fn check_overlap(rules: &[Rule], result: &mut LintResult) {
+        for rule in rules {
+            if rule.condition != RuleCondition::Contain || rule.keyword.is_empty() {
+                continue;
+            }
+            let wider = rules.iter().find(|other| other.condition == RuleCondition::Contain && other.scope == rule.scope
+                && other.keyword != rule.keyword && other.keyword.contains(&rule.keyword));
+            if let Some(wider) = wider {
+                result.push(rule, LintLevel::Warning, format!("关键字[{}]包含在规则[{}]的关键字[{}]中,命中规则[{}]的代码都会同时命中本规则", rule.keyword, wider.id, wider.keyword, wider.id));
+            }
+        }
+    }
+
+    // 统一换算为正则后比较，Contain规则os/exec与Regex规则os/exec视为相同
+    fn pattern(rule: &Rule) -> String {
+        match rule.condition {
+            RuleCondition::Contain => regex::escape(&rule.keyword),
+            RuleCondition::Regex => rule.keyword.clone(),
+        }
+    }
+
+    /**
+     * @descript 执行规则示例，正例需要命中规则，反例不能命中规则
+     * 示例按完整的源码文件处理，会经过词法分析、跨行匹配和组合条件，与扫描时的结果一致
+     */
+    fn run_examples(config: &Config, rule: &Rule, matcher: &RuleMatcher, example_ext: &str, result: &mut LintResult) {
+        let ext = rule.include_ext.first().map(String::as_str).unwrap_or(example_ext).trim_start_matches('.');
+        let file_path = format!("example.{}", ext);
+        let rule_ids: HashSet<&str> = HashSet::from([rule.id.as_str()]);
+        let examples = rule.examples.positive.iter().map(|example| (true, example))
+            .chain(rule.examples.negative.iter().map(|example| (false, example)));
+        for (index, (positive, example)) in examples.enumerate() {
+            let lines = example.lines().map(|line| Ok(line.to_string()));
+            let (findings, _, _) = RuleCheck::check_source(config, slice::from_ref(rule), matcher, &[true], &rule_ids, &file_path, lines);
+            let hit_lines: Vec<usize> = findings.iter().filter(|finding| finding.suppression.is_none()).map(|finding| finding.line).collect();
+            result.examples += 1;
+            let number = if positive { index + 1 } else { index + 1 - rule.examples.positive.len() };
+            match (positive, hit_lines.is_empty()) {
+                (true, true) => result.push(rule, LintLevel::Error, format!("正例{}未命中规则: {}", number, Self::preview(example))),
+                (false, false) => result.push(rule, LintLevel::Error, format!("反例{}在第{:?}行命中了规则: {}", number, hit_lines, Self::preview(example))),
+                _ => result.passed += 1,
+            }
+        }
+    }
+
+    // 示例只展示第一行，避免多行示例刷屏
+    fn preview(example: &str) -> String {
+        let first_line = example.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+        match example.trim().lines().count() > 1 {
+            true => format!("{} ...", first_line),
+            false => first_line.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn config(lang_type: &str, rules: Vec<Rule>) -> Config {
+        let mut config: Config = serde_json::from_value(json!({
+            "project_name": "lint",
+            "lang_type": lang_type,
+            "work_dir": ".",
+            "exclude_path": [],
+            "collection_interface": false,
+            "framework": "None",
+            "orm": "None",
+            "depency_file": "",
+            "use_ui": false,
+            "unauthorized": {"prefix": "", "valid": false, "rule": {"status_code": [], "response_body": []}}
+        })).unwrap();
+        config.rule = rules;
+        config
+    }
+
+    fn rules(values: Value) -> Vec<Rule> {
+        let mut values = values;
+        for value in values.as_array_mut().unwrap() {
+            value["note"] = json!("");
+        }
+        serde_json::from_value(values).unwrap()
+    }
+
+    // 规则来自同一个规则文件时的检查结果，只保留规则id和级别
+    fn lint(values: Value) -> Vec<(String, LintLevel, String)> {
+        let rules = rules(values);
+        let sources = [RuleSource { name: String::from("rules.json"), rules: rules.clone() }];
+        RuleLint::lint(&config("JAVA", rules), &sources, "java").issues.into_iter()
+            .map(|issue| (issue.rule_id, issue.level, issue.message))
+            .collect()
+    }
+
+    fn levels(issues: &[(String, LintLevel, String)]) -> Vec<(&str, LintLevel)> {
+        issues.iter().map(|(rule_id, level, _)| (rule_id.as_str(), *level)).collect()
+    }
+
+    #[test]
+    fn contain_keyword_pattern() {
+        let issues = lint(json!([
+            {"id": "empty", "keyword": "", "condition": "Contain"},
+            {"id": "short", "keyword": "net", "condition": "Contain"},
+            {"id": "regex-like", "keyword": r"getInstance\(.*\)", "condition": "Contain"},
+            {"id": "ok", "keyword": "os/exec", "condition": "Contain"},
+        ]));
+        assert_eq!(levels(&issues), vec![
+            ("empty", LintLevel::Error),
+            ("short", LintLevel::Warning),
+            ("regex-like", LintLevel::Warning),
+        ]);
+        assert!(issues[1].2.contains("过短"));
+        assert!(issues[2].2.contains("看起来是正则"));
+    }
+
+    #[test]
+    fn regex_keyword_pattern() {
+        let issues = lint(json!([
+            {"id": "invalid", "keyword": "exec(", "condition": "Regex"},
+            {"id": "empty-match", "keyword": "(eval)?", "condition": "Regex"},
+            {"id": "plain", "keyword": "database/sql", "condition": "Regex"},
+            {"id": "plain-short", "keyword": "sql", "condition": "Regex"},
+            {"id": "empty-group", "keyword": r"\bexec()", "condition": "Regex"},
+            {"id": "ok", "keyword": r"\bexec\s*\(", "condition": "Regex"},
+        ]));
+        assert_eq!(levels(&issues), vec![
+            ("invalid", LintLevel::Error),
+            ("empty-match", LintLevel::Error),
+            ("plain", LintLevel::Warning),
+            ("plain-short", LintLevel::Warning),
+            ("plain-short", LintLevel::Warning),
+            ("empty-group", LintLevel::Warning),
+        ]);
+        assert!(issues[2].2.contains("建议改为Contain"));
+        assert!(issues[5].2.contains("空分组"));
+    }
+
+    #[test]
+    fn overlapping_contain_keywords() {
+        let issues = lint(json!([
+            {"id": "php-exec", "keyword": "exec(", "condition": "Contain"},
+            {"id": "php-shell-exec", "keyword": "shell_exec(", "condition": "Contain"},
+            {"id": "go-net", "keyword": "net.Dial", "condition": "Contain"},
+            {"id": "go-net-http", "keyword": "net/http", "condition": "Contain"},
+            {"id": "file-scope", "keyword": "shell_exec(", "condition": "Contain", "scope": "file"},
+        ]));
+        assert_eq!(levels(&issues), vec![("php-exec", LintLevel::Warning)]);
+        assert!(issues[0].2.contains("[php-shell-exec]"));
+        // 改为带单词边界的正则后不再与shell_exec(重叠
+        assert_eq!(lint(json!([
+            {"id": "php-exec", "keyword": r"\bexec\s*\(", "condition": "Regex",
+                "examples": {"positive": ["exec($cmd);"], "negative": ["shell_exec($cmd);"]}},
+            {"id": "php-shell-exec", "keyword": "shell_exec(", "condition": "Contain"},
+        ])), vec![]);
+    }
+
+    #[test]
+    fn duplicate_rules_in_sources() {
+        let first = rules(json!([
+            {"id": "exec", "keyword": "exec(", "condition": "Contain"},
+            {"id": "exec", "keyword": "Runtime.getRuntime", "condition": "Contain"},
+        ]));
+        let second = rules(json!([
+            {"id": "regex-exec", "keyword": r"exec\(", "condition": "Regex"},
+            {"id": "exec", "keyword": "new ProcessBuilder(", "condition": "Contain"},
+        ]));
+        let sources = [
+            RuleSource { name: String::from("first.json"), rules: first.clone() },
+            RuleSource { name: String::from("second.json"), rules: second },
+        ];
+        let mut config = config("JAVA", vec![]);
+        config.merge_rules(sources.to_vec());
+        let issues = RuleLint::lint(&config, &sources, "java").issues;
+        let messages: Vec<&str> = issues.iter().map(|issue| issue.message.as_str()).collect();
+        // 同一文件中的id重复是错误，不同文件中相同id是覆盖，不同id的相同关键字会重复报告
+        assert_eq!(messages, vec![
+            "规则文件[first.json]中第2条规则与第1条规则的id相同,id需要唯一",
+            "规则文件[second.json]中第1条规则的关键字与规则文件[first.json]中第1条规则[exec]相同,同一处代码会重复报告",
+        ]);
+        assert_eq!(issues[0].level, LintLevel::Error);
+        assert_eq!(issues[1].level, LintLevel::Warning);
+    }
+
+    #[test]
+    fn run_examples() {
+        let rules = rules(json!([
+            {"id": "eval", "keyword": r"\beval\s*\(", "condition": "Regex", "examples": {
+                "positive": ["eval(code);", "// eval(code);"],
+                "negative": ["evaluate(code);", "eval(code);"],
+            }},
+        ]));
+        let sources = [RuleSource { name: String::from("rules.json"), rules: rules.clone() }];
+        let result = RuleLint::lint(&config("JAVA", rules), &sources, "java");
+        assert_eq!((result.examples, result.passed), (4, 2));
+        let messages: Vec<&str> = result.issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(messages, vec!["正例2未命中规则: // eval(code);", "反例2在第[1]行命中了规则: eval(code);"]);
+        assert_eq!(result.count(LintLevel::Error), 2);
+    }
+
+    #[test]
+    fn builtin_rule_packs() {
+        for (lang_type, pack, ext) in [("JAVA", "java", "java"), ("PHP", "php", "php"), ("GO", "go", "go")] {
+            let rule_file = format!("{}/rules/{}.json", env!("CARGO_MANIFEST_DIR"), pack);
+            let rules: Vec<Rule> = serde_json::from_str(&fs::read_to_string(&rule_file).unwrap()).unwrap();
+            let sources = [RuleSource { name: rule_file.clone(), rules: rules.clone() }];
+            let result = RuleLint::lint(&config(lang_type, rules), &sources, ext);
+            assert!(result.issues.is_empty(), "{}: {:?}", rule_file, result.issues);
+            assert_eq!(result.passed, result.examples);
+        }
+    }
+}
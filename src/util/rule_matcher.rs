@@ -0,0 +1,562 @@
+use std::path::Path;
+
+use aho_corasick::AhoCorasick;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::{Regex, RegexSet};
+
+use crate::{enums::{RuleCondition, RuleScope}, error::ScanError, model::{condition::{CompositeCondition, Pattern}, rule::Rule}};
+
+/**
+ * @Struct RuleMatcher 预编译的规则匹配器，规则只在扫描开始前编译一次
+ * @Field contain_automaton Contain规则关键字构建的多模式自动机
+ * @Field contain_rules 自动机中模式下标对应的规则下标
+ * @Field regex_set Regex规则构建的正则集合
+ * @Field regex_rules 正则集合中下标对应的规则下标
+ * @Field regexes 正则集合中下标对应的单条正则，用于获取命中位置
+ * @Field multiline_rules 跨行匹配的规则，记录规则下标、编译后的正则和匹配范围
+ * @Field conditions 规则下标对应的编译后的组合条件，未配置组合条件的规则为None
+ * @Field file_filters 规则下标对应的文件过滤条件，未配置后缀和路径过滤的规则为None
+ */
+pub struct RuleMatcher {
+
+    contain_automaton: AhoCorasick,
+
+    contain_rules: Vec<usize>,
+
+    regex_set: RegexSet,
+
+    regex_rules: Vec<usize>,
+
+    regexes: Vec<Regex>,
+
+    multiline_rules: Vec<(usize, Regex, RuleScope)>,
+
+    conditions: Vec<Option<CompiledCondition>>,
+
+    file_filters: Vec<Option<FileFilter>>,
+
+}
+
+/**
+ * @Struct FileFilter 编译后的规则文件过滤条件
+ * @Field include_ext 只检查的后缀，小写
+ * @Field include_paths 只检查的路径
+ * @Field exclude_paths 不检查的路径
+ */
+struct FileFilter {
+    include_ext: Vec<String>,
+    include_paths: Option<GlobSet>,
+    exclude_paths: Option<GlobSet>,
+}
+
+impl FileFilter {
+
+    /**
+     * @descript 编译规则的文件过滤条件
+     * @return 规则没有配置后缀和路径过滤时返回None
+     */
+    fn compile(rule: &Rule) -> Result<Option<Self>, ScanError> {
+        if rule.include_ext.is_empty() && rule.include_paths.is_empty() && rule.exclude_paths.is_empty() {
+            return Ok(None)
+        }
+        let build = |globs: &[String]| -> Result<Option<GlobSet>, ScanError> {
+            if globs.is_empty() {
+                return Ok(None)
+            }
+            let mut builder = GlobSetBuilder::new();
+            for glob in globs {
+                match Glob::new(glob) {
+                    Ok(glob) => builder.add(glob),
+                    Err(err) => return Err(ScanError::Rule(format!("规则[{}]的路径: [{}]不是合法的glob: {}", rule.id, glob, err))),
+                };
+            }
+            builder.build().map(Some).map_err(|err| ScanError::Rule(format!("规则[{}]的路径编译失败: {}", rule.id, err)))
+        };
+        Ok(Some(FileFilter {
+            include_ext: rule.include_ext.iter().map(|ext| ext.trim_start_matches('.').to_lowercase()).collect(),
+            include_paths: build(&rule.include_paths)?,
+            exclude_paths: build(&rule.exclude_paths)?,
+        }))
+    }
+
+    fn is_match(&self, relative_path: &str) -> bool {
+        let ext = Path::new(relative_path).extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        (self.include_ext.is_empty() || self.include_ext.contains(&ext))
+            && self.include_paths.as_ref().is_none_or(|globs| globs.is_match(relative_path))
+            && !self.exclude_paths.as_ref().is_some_and(|globs| globs.is_match(relative_path))
+    }
+}
+
+/**
+ * 编译后的组合条件，与CompositeCondition一一对应
+ */
+enum CompiledCondition {
+    All(Vec<CompiledCondition>),
+    Any(Vec<CompiledCondition>),
+    Not(Box<CompiledCondition>),
+    FileContains(CompiledPattern),
+    FileNotContains(CompiledPattern),
+    WithinLines(usize, CompiledPattern),
+}
+
+/**
+ * 编译后的查找内容
+ */
+enum CompiledPattern {
+    Literal(String),
+    Regex(Regex),
+}
+
+impl CompiledCondition {
+
+    /**
+     * @descript 编译组合条件
+     * @param condition 规则中配置的组合条件
+     * @param rule_id 规则id，用于错误提示
+     */
+    fn compile(condition: &CompositeCondition, rule_id: &str) -> Result<Self, ScanError> {
+        let compile_all = |conditions: &[CompositeCondition]| conditions.iter()
+            .map(|condition| Self::compile(condition, rule_id))
+            .collect::<Result<Vec<CompiledCondition>, ScanError>>();
+        Ok(match condition {
+            CompositeCondition::All(conditions) => CompiledCondition::All(compile_all(conditions)?),
+            CompositeCondition::Any(conditions) => CompiledCondition::Any(compile_all(conditions)?),
+            CompositeCondition::Not(condition) => CompiledCondition::Not(Box::new(Self::compile(condition, rule_id)?)),
+            CompositeCondition::FileContains(pattern) => CompiledCondition::FileContains(CompiledPattern::compile(pattern, rule_id)?),
+            CompositeCondition::FileNotContains(pattern) => CompiledCondition::FileNotContains(CompiledPattern::compile(pattern, rule_id)?),
+            CompositeCondition::WithinLines(proximity) => CompiledCondition::WithinLines(proximity.lines, CompiledPattern::compile(&proximity.pattern, rule_id)?),
+        })
+    }
+
+    /**
+     * @descript 判断条件是否成立
+     * @param lines 文件的全部行
+     * @param content 文件的全部内容
+     * @param start_line 命中开始的行下标
+     * @param end_line 命中结束的行下标
+     */
+    fn holds(&self, lines: &[String], content: &str, start_line: usize, end_line: usize) -> bool {
+        match self {
+            CompiledCondition::All(conditions) => conditions.iter().all(|condition| condition.holds(lines, content, start_line, end_line)),
+            CompiledCondition::Any(conditions) => conditions.iter().any(|condition| condition.holds(lines, content, start_line, end_line)),
+            CompiledCondition::Not(condition) => !condition.holds(lines, content, start_line, end_line),
+            CompiledCondition::FileContains(pattern) => pattern.is_match(content),
+            CompiledCondition::FileNotContains(pattern) => !pattern.is_match(content),
+            CompiledCondition::WithinLines(distance, pattern) => {
+                let begin = start_line.saturating_sub(*distance);
+                let end = (end_line + distance + 1).min(lines.len());
+                pattern.is_match(&lines[begin..end].join("\n"))
+            },
+        }
+    }
+}
+
+impl CompiledPattern {
+
+    fn compile(pattern: &Pattern, rule_id: &str) -> Result<Self, ScanError> {
+        match pattern {
+            Pattern::Literal(literal) => Ok(CompiledPattern::Literal(literal.clone())),
+            Pattern::Regex { regex } => match Regex::new(regex) {
+                Ok(compiled) => Ok(CompiledPattern::Regex(compiled)),
+                Err(err) => Err(ScanError::Rule(format!("规则[{}]组合条件中的正则: [{}]不是合法的表达式: {}", rule_id, regex, err))),
+            },
+        }
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            CompiledPattern::Literal(literal) => text.contains(literal.as_str()),
+            CompiledPattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/**
+ * @Struct MultilineMatch 跨行规则的一次命中，行号均为从0开始的下标
+ * @Field rule 命中的规则下标
+ * @Field start_line 命中开始的行
+ * @Field start_column 命中开始位置在该行中的字节位置
+ * @Field end_line 命中结束的行
+ */
+#[derive(Debug)]
+pub struct MultilineMatch {
+
+    pub rule: usize,
+
+    pub start_line: usize,
+
+    pub start_column: usize,
+
+    pub end_line: usize,
+
+}
+
+impl RuleMatcher {
+
+    /**
+     * @descript 编译规则列表
+     * @param rules 规则列表
+     * @return 编译后的匹配器，存在非法正则时返回错误
+     */
+    pub fn new(rules: &[Rule]) -> Result<Self, ScanError> {
+        let mut contain_patterns: Vec<&str> = vec![];
+        let mut contain_rules: Vec<usize> = vec![];
+        let mut regex_patterns: Vec<&str> = vec![];
+        let mut regex_rules: Vec<usize> = vec![];
+        let mut regexes: Vec<Regex> = vec![];
+        let mut multiline_rules: Vec<(usize, Regex, RuleScope)> = vec![];
+        let mut conditions: Vec<Option<CompiledCondition>> = Vec::with_capacity(rules.len());
+        let mut file_filters: Vec<Option<FileFilter>> = Vec::with_capacity(rules.len());
+        for (index, rule) in rules.iter().enumerate() {
+            file_filters.push(FileFilter::compile(rule)?);
+            conditions.push(match &rule.when {
+                Some(condition) => Some(CompiledCondition::compile(condition, &rule.id)?),
+                None => None,
+            });
+            // 跨行规则单独编译，Contain规则按字面量处理
+            if rule.scope != RuleScope::Line {
+                let pattern = match rule.condition {
+                    RuleCondition::Contain => regex::escape(&rule.keyword),
+                    RuleCondition::Regex => rule.keyword.clone(),
+                };
+                match Regex::new(&pattern) {
+                    Ok(regex) => multiline_rules.push((index, regex, rule.scope)),
+                    Err(err) => return Err(ScanError::Rule(format!("规则[{}]的正则: [{}]不是合法的表达式: {}", rule.id, rule.keyword, err))),
+                }
+                continue;
+            }
+            match rule.condition {
+                RuleCondition::Contain => {
+                    contain_patterns.push(&rule.keyword);
+                    contain_rules.push(index);
+                },
+                RuleCondition::Regex => {
+                    // 逐条校验，便于指出是哪一条规则的正则有误
+                    match Regex::new(&rule.keyword) {
+                        Ok(regex) => regexes.push(regex),
+                        Err(err) => return Err(ScanError::Rule(format!("规则[{}]的正则: [{}]不是合法的表达式: {}", rule.id, rule.keyword, err))),
+                    }
+                    regex_patterns.push(&rule.keyword);
+                    regex_rules.push(index);
+                },
+            }
+        }
+        Ok(RuleMatcher {
+            contain_automaton: AhoCorasick::new(&contain_patterns).map_err(|err| ScanError::Rule(format!("Contain规则编译失败: {}", err)))?,
+            contain_rules,
+            regex_set: RegexSet::new(&regex_patterns).map_err(|err| ScanError::Rule(format!("Regex规则编译失败: {}", err)))?,
+            regex_rules,
+            regexes,
+            multiline_rules,
+            conditions,
+            file_filters,
+        })
+    }
+
+    /**
+     * @descript 判断规则是否需要检查指定的文件
+     * @param rule 规则下标
+     * @param relative_path 相对于work_dir的文件路径，使用/分隔
+     */
+    pub fn applies_to_file(&self, rule: usize, relative_path: &str) -> bool {
+        match &self.file_filters[rule] {
+            Some(filter) => filter.is_match(relative_path),
+            None => true,
+        }
+    }
+
+    /**
+     * @descript 是否存在配置了组合条件的规则
+     */
+    pub fn has_conditions(&self) -> bool {
+        self.conditions.iter().any(Option::is_some)
+    }
+
+    /**
+     * @descript 判断规则的组合条件是否成立，未配置组合条件的规则始终成立
+     * @param rule 规则下标
+     * @param lines 文件的全部行
+     * @param content 文件的全部内容
+     * @param start_line 命中开始的行下标
+     * @param end_line 命中结束的行下标
+     */
+    pub fn condition_holds(&self, rule: usize, lines: &[String], content: &str, start_line: usize, end_line: usize) -> bool {
+        match &self.conditions[rule] {
+            Some(condition) => condition.holds(lines, content, start_line, end_line),
+            None => true,
+        }
+    }
+
+    /**
+     * @descript 是否存在跨行匹配的规则
+     */
+    pub fn has_multiline(&self) -> bool {
+        !self.multiline_rules.is_empty()
+    }
+
+    /**
+     * @descript 在整个文件上执行跨行规则，同一处代码只在命中开始的行报告一次
+     * @param lines 文件的全部行
+     * @return 跨行规则的全部命中，按规则顺序和位置排列
+     */
+    pub fn matches_multiline(&self, lines: &[String]) -> Vec<MultilineMatch> {
+        let mut hits: Vec<MultilineMatch> = vec![];
+        if self.multiline_rules.is_empty() || lines.is_empty() {
+            return hits
+        }
+        // 每行在拼接后内容中的起始位置，用于将位置换算回行号
+        let mut line_starts: Vec<usize> = Vec::with_capacity(lines.len());
+        let mut offset = 0;
+        for line in lines {
+            line_starts.push(offset);
+            offset += line.len() + 1;
+        }
+        let content = lines.join("\n");
+        let line_of = |offset: usize| line_starts.partition_point(|start| *start <= offset) - 1;
+        for (rule, regex, scope) in &self.multiline_rules {
+            match scope {
+                RuleScope::Window(window) => {
+                    for start_line in 0..lines.len() {
+                        let end = (start_line + window).min(lines.len());
+                        let window_begin = line_starts[start_line];
+                        let window_end = line_starts[end - 1] + lines[end - 1].len();
+                        let text = &content[window_begin..window_end];
+                        // 只保留从窗口第一行开始的命中，避免相邻窗口重复报告
+                        for hit in regex.find_iter(text).take_while(|hit| hit.start() <= lines[start_line].len()) {
+                            hits.push(MultilineMatch {
+                                rule: *rule,
+                                start_line,
+                                start_column: hit.start(),
+                                end_line: line_of(window_begin + hit.end().saturating_sub(1).max(hit.start())),
+                            });
+                        }
+                    }
+                },
+                _ => {
+                    for hit in regex.find_iter(&content) {
+                        let start_line = line_of(hit.start());
+                        hits.push(MultilineMatch {
+                            rule: *rule,
+                            start_line,
+                            start_column: hit.start() - line_starts[start_line],
+                            end_line: line_of(hit.end().saturating_sub(1).max(hit.start())),
+                        });
+                    }
+                },
+            }
+        }
+        hits
+    }
+
+    /**
+     * @descript 获取一行数据命中的全部规则以及每条规则命中的位置
+     * @param data 要进行检查的数据
+     * @return Vec<(usize, Vec<usize>)> 命中的规则下标和该规则所有命中的起始字节位置，按规则顺序排列且不重复
+     */
+    pub fn matches(&self, data: &str) -> Vec<(usize, Vec<usize>)> {
+        let mut hits: Vec<(usize, usize)> = self.contain_automaton
+            .find_overlapping_iter(data)
+            .map(|hit| (self.contain_rules[hit.pattern().as_usize()], hit.start()))
+            .collect();
+        // 正则集合只能判断是否命中，命中后再用单条正则获取位置
+        for index in self.regex_set.matches(data).into_iter() {
+            hits.extend(self.regexes[index].find_iter(data).map(|hit| (self.regex_rules[index], hit.start())));
+        }
+        hits.sort_unstable();
+        hits.dedup();
+        let mut grouped: Vec<(usize, Vec<usize>)> = vec![];
+        for (rule, start) in hits {
+            match grouped.last_mut() {
+                Some((last_rule, starts)) if *last_rule == rule => starts.push(start),
+                _ => grouped.push((rule, vec![start])),
+            }
+        }
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn rule(value: Value) -> Rule {
+        let mut value = value;
+        value["note"] = json!("");
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn lines(source: &str) -> Vec<String> {
+        source.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn contain_and_regex_rules() {
+        let rules = [
+            rule(json!({"id": "exec", "keyword": "exec(", "condition": "Contain"})),
+            rule(json!({"id": "runtime", "keyword": "Runtime.getRuntime().exec(", "condition": "Contain"})),
+            rule(json!({"id": "sql", "keyword": r"(?i)select\s+.*\+", "condition": "Regex"})),
+            rule(json!({"id": "md5", "keyword": r#"getInstance\("MD5"\)"#, "condition": "Regex"})),
+        ];
+        let matcher = RuleMatcher::new(&rules).unwrap();
+        assert!(!matcher.has_multiline());
+        assert!(!matcher.has_conditions());
+
+        // 重叠的Contain关键字都会命中，同一条规则的多处命中合并
+        let line = "Runtime.getRuntime().exec(cmd); exec(other);";
+        assert_eq!(matcher.matches(line), vec![(0, vec![21, 32]), (1, vec![0])]);
+
+        let line = "query(\"SELECT * FROM t WHERE id = \" + id); MessageDigest.getInstance(\"MD5\");";
+        assert_eq!(matcher.matches(line), vec![(2, vec![7]), (3, vec![line.find("getInstance").unwrap()])]);
+
+        assert!(matcher.matches("String s = \"safe\";").is_empty());
+    }
+
+    #[test]
+    fn contain_keyword_is_literal() {
+        let rules = [rule(json!({"id": "dot", "keyword": "a.b(", "condition": "Contain"}))];
+        let matcher = RuleMatcher::new(&rules).unwrap();
+        assert_eq!(matcher.matches("a.b(x)"), vec![(0, vec![0])]);
+        assert!(matcher.matches("axb(x)").is_empty());
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let rules = [rule(json!({"id": "broken", "keyword": "exec(", "condition": "Regex"}))];
+        match RuleMatcher::new(&rules) {
+            Err(ScanError::Rule(message)) => assert!(message.contains("broken"), "{}", message),
+            _ => panic!("非法的正则应返回规则错误"),
+        }
+    }
+
+    #[test]
+    fn file_scope_across_lines() {
+        let rules = [
+            rule(json!({"id": "process-builder", "keyword": r"new\s+ProcessBuilder\(\s*cmd", "condition": "Regex", "scope": "file"})),
+            rule(json!({"id": "line-only", "keyword": r"new\s+ProcessBuilder\(\s*cmd", "condition": "Regex"})),
+        ];
+        let matcher = RuleMatcher::new(&rules).unwrap();
+        assert!(matcher.has_multiline());
+        let source = lines("void run() {\n    Process p = new ProcessBuilder(\n        cmd).start();\n}");
+        // 逐行匹配的规则无法命中跨行的代码
+        assert!(source.iter().all(|line| matcher.matches(line).is_empty()));
+        let hits = matcher.matches_multiline(&source);
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].rule, hits[0].start_line, hits[0].start_column, hits[0].end_line), (0, 1, 16, 2));
+    }
+
+    #[test]
+    fn window_scope() {
+        let rules = [rule(json!({"id": "window", "keyword": r"query\([^)]*\+", "condition": "Regex", "scope": "window(2)"}))];
+        let matcher = RuleMatcher::new(&rules).unwrap();
+        // 窗口内的跨行命中只在开始的行报告一次
+        let hits = matcher.matches_multiline(&lines("a();\nquery(\"select \"\n + id);\nb();"));
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].start_line, hits[0].end_line), (1, 2));
+        // 超出窗口的行不会命中
+        assert!(matcher.matches_multiline(&lines("query(\"select \"\n\n + id);")).is_empty());
+        // Contain关键字在跨行规则中按字面量匹配
+        let rules = [rule(json!({"id": "literal", "keyword": "a.b", "condition": "Contain", "scope": "window(3)"}))];
+        let matcher = RuleMatcher::new(&rules).unwrap();
+        assert_eq!(matcher.matches_multiline(&lines("axb\na.b")).len(), 1);
+    }
+
+    #[test]
+    fn scope_values() {
+        assert_eq!(RuleScope::try_from(String::from("line")), Ok(RuleScope::Line));
+        assert_eq!(RuleScope::try_from(String::from("file")), Ok(RuleScope::File));
+        assert_eq!(RuleScope::try_from(String::from("window( 5 )")), Ok(RuleScope::Window(5)));
+        assert!(RuleScope::try_from(String::from("window(1)")).is_err());
+        assert!(RuleScope::try_from(String::from("block")).is_err());
+    }
+
+    // 编译只有一条规则的匹配器，判断该规则在第start_line行的命中是否满足组合条件
+    fn holds(when: Value, source: &str, start_line: usize) -> bool {
+        let rules = [rule(json!({"id": "exec", "keyword": "exec(", "condition": "Contain", "when": when}))];
+        let matcher = RuleMatcher::new(&rules).unwrap();
+        assert!(matcher.has_conditions());
+        matcher.condition_holds(0, &lines(source), source, start_line, start_line)
+    }
+
+    #[test]
+    fn file_contains() {
+        let source = "import java.lang.Runtime;\nexec(cmd);";
+        assert!(holds(json!({"file_contains": "java.lang.Runtime"}), source, 1));
+        assert!(!holds(json!({"file_contains": "ProcessBuilder"}), source, 1));
+        assert!(holds(json!({"file_contains": {"regex": r"import\s+java\.lang\.\w+;"}}), source, 1));
+        assert!(holds(json!({"file_not_contains": "Validator"}), source, 1));
+        assert!(!holds(json!({"file_not_contains": "Runtime"}), source, 1));
+    }
+
+    #[test]
+    fn within_lines() {
+        let source = "String cmd = request.getParameter(\"c\");\nlog(cmd);\nfoo();\nexec(cmd);";
+        assert!(holds(json!({"within_lines": {"lines": 3, "pattern": "getParameter"}}), source, 3));
+        assert!(!holds(json!({"within_lines": {"lines": 2, "pattern": "getParameter"}}), source, 3));
+        assert!(holds(json!({"within_lines": {"lines": 0, "pattern": {"regex": r"exec\(\w+\)"}}}), source, 3));
+    }
+
+    #[test]
+    fn all_any_not() {
+        let source = "String cmd = request.getParameter(\"c\");\nexec(cmd);";
+        let tainted = json!({"within_lines": {"lines": 1, "pattern": "getParameter"}});
+        let sanitized = json!({"file_contains": "escapeShell"});
+        assert!(holds(json!({"all": [tainted, {"not": sanitized}]}), source, 1));
+        assert!(!holds(json!({"all": [tainted, {"file_contains": "ProcessBuilder"}]}), source, 1));
+        assert!(holds(json!({"any": [{"file_contains": "ProcessBuilder"}, tainted]}), source, 1));
+        assert!(!holds(json!({"any": [{"file_contains": "ProcessBuilder"}, sanitized]}), source, 1));
+        // 文件中有过滤方法时not条件不成立，不报告
+        let sanitized_source = "String cmd = escapeShell(request.getParameter(\"c\"));\nexec(cmd);";
+        assert!(!holds(json!({"all": [tainted, {"not": sanitized}]}), sanitized_source, 1));
+        assert!(!holds(json!({"not": {"within_lines": {"lines": 1, "pattern": "getParameter"}}}), source, 1));
+    }
+
+    #[test]
+    fn rules_without_conditions_always_hold() {
+        let rules = [rule(json!({"id": "exec", "keyword": "exec(", "condition": "Contain"}))];
+        let matcher = RuleMatcher::new(&rules).unwrap();
+        assert!(matcher.condition_holds(0, &lines("exec(cmd);"), "exec(cmd);", 0, 0));
+    }
+
+    #[test]
+    fn invalid_condition_regex() {
+        let rules = [rule(json!({"id": "exec", "keyword": "exec(", "condition": "Contain", "when": {"file_contains": {"regex": "("}}}))];
+        match RuleMatcher::new(&rules) {
+            Err(ScanError::Rule(message)) => assert!(message.contains("组合条件"), "{}", message),
+            _ => panic!("组合条件中非法的正则应返回规则错误"),
+        }
+    }
+
+    #[test]
+    fn file_filters() {
+        let rules = [
+            rule(json!({"id": "all", "keyword": "exec(", "condition": "Contain"})),
+            rule(json!({"id": "ext", "keyword": "exec(", "condition": "Contain", "include_ext": [".JSP", "java"]})),
+            rule(json!({"id": "paths", "keyword": "exec(", "condition": "Contain", "include_paths": ["src/main/**"], "exclude_paths": ["**/test/**", "**/*Test.java"]})),
+        ];
+        let matcher = RuleMatcher::new(&rules).unwrap();
+        let cases = [
+            ("src/main/java/A.java", [true, true, true]),
+            ("src/main/webapp/index.jsp", [true, true, true]),
+            ("src/main/resources/app.xml", [true, false, true]),
+            ("src/main/java/ATest.java", [true, true, false]),
+            ("src/main/test/B.java", [true, true, false]),
+            ("lib/C.java", [true, true, false]),
+            ("README", [true, false, false]),
+        ];
+        for (path, expected) in cases {
+            let applies: Vec<bool> = (0..rules.len()).map(|index| matcher.applies_to_file(index, path)).collect();
+            assert_eq!(applies, expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn invalid_glob() {
+        let rules = [rule(json!({"id": "glob", "keyword": "exec(", "condition": "Contain", "include_paths": ["src/[a"]}))];
+        match RuleMatcher::new(&rules) {
+            Err(ScanError::Rule(message)) => assert!(message.contains("glob"), "{}", message),
+            _ => panic!("非法的glob应返回规则错误"),
+        }
+    }
+}
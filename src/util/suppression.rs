@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use crate::{enums::SpanKind, model::finding::Suppression};
+
+use super::lexer::LineSpans;
+
+// 忽略同一行或下一行命中的标记
+const IGNORE_MARKER: &str = "code_scan:ignore";
+// 忽略整个文件命中的标记
+const IGNORE_FILE_SUFFIX: &str = "-file";
+// 标记后面需要去掉的块注释结束符
+const COMMENT_ENDS: [&str; 2] = ["*/", "-->"];
+
+/**
+ * @Struct SuppressionMarker 源码注释中的忽略标记
+ * 格式为`code_scan:ignore <rule-id>[,<rule-id>] 原因`或`code_scan:ignore-file [<rule-id>] 原因`
+ * @Field file_level 是否对整个文件生效
+ * @Field all_rules 是否忽略所有规则，标记中使用*或没有写规则id时为true
+ * @Field rule_ids 忽略的规则id
+ * @Field unknown_rule_ids 标记中写了但未加载的规则id，这些id不会忽略任何规则
+ * @Field standalone 标记所在行在标记之前是否只有注释，只有注释的标记才对下一行生效
+ * @Field line 标记所在的行号
+ * @Field reason 忽略的原因
+ */
+#[derive(Debug, Clone)]
+pub struct SuppressionMarker {
+
+    pub file_level: bool,
+
+    pub all_rules: bool,
+
+    pub rule_ids: Vec<String>,
+
+    pub unknown_rule_ids: Vec<String>,
+
+    pub standalone: bool,
+
+    pub line: usize,
+
+    pub reason: String,
+
+}
+
+impl SuppressionMarker {
+
+    /**
+     * @descript 从一行源码中解析忽略标记，标记必须位于词法分析得到的注释区域中，字符串中的标记不会生效
+     * @param line 源码行
+     * @param line_number 行号
+     * @param spans 该行的区域划分
+     * @param known_rule_ids 已加载的规则id，标记后第一个单词是规则id列表时按id忽略，写错或已删除的id不会忽略任何规则
+     * @return 未找到标记时返回None
+     */
+    pub fn parse(line: &str, line_number: usize, spans: &LineSpans, known_rule_ids: &HashSet<&str>) -> Option<Self> {
+        let (position, _) = line.match_indices(IGNORE_MARKER).find(|(position, _)| spans.kind_at(*position) == SpanKind::Comment)?;
+        // 标记之前只有空白和注释时单独成行
+        let standalone = line[..position].char_indices().all(|(offset, char)| char.is_whitespace() || spans.kind_at(offset) == SpanKind::Comment);
+        let mut rest = &line[position + IGNORE_MARKER.len()..];
+        let file_level = rest.starts_with(IGNORE_FILE_SUFFIX);
+        if file_level {
+            rest = &rest[IGNORE_FILE_SUFFIX.len()..];
+        }
+        // 避免把code_scan:ignored之类的单词当成标记
+        if rest.chars().next().is_some_and(|next| !next.is_whitespace()) {
+            return None
+        }
+        let mut rest = rest.trim();
+        for end in COMMENT_ENDS {
+            rest = rest.strip_suffix(end).unwrap_or(rest).trim_end();
+        }
+        let (first, reason) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let ids: Vec<&str> = first.split(',').filter(|id| !id.is_empty()).collect();
+        // 第一个单词中有已加载的规则id，或者形如规则id(包含-、_、,或*)时视为规则列表，否则视为原因
+        let is_rule_list = !ids.is_empty() && ids.iter().all(|id| id.chars().all(|char| char.is_ascii_alphanumeric() || matches!(char, '-' | '_' | '.' | '*')))
+            && (first.contains([',', '-', '_', '*']) || ids.iter().any(|id| known_rule_ids.contains(id)));
+        let (ids, reason) = match is_rule_list {
+            true => (ids, reason.trim()),
+            false => (vec![], rest),
+        };
+        let all_rules = ids.is_empty() || ids.contains(&"*");
+        let (rule_ids, unknown_rule_ids): (Vec<&str>, Vec<&str>) = ids.into_iter()
+            .filter(|id| *id != "*")
+            .partition(|id| known_rule_ids.contains(id));
+        Some(SuppressionMarker {
+            file_level,
+            all_rules,
+            rule_ids: rule_ids.into_iter().map(String::from).collect(),
+            unknown_rule_ids: unknown_rule_ids.into_iter().map(String::from).collect(),
+            standalone,
+            line: line_number,
+            reason: reason.to_string(),
+        })
+    }
+
+    /**
+     * @descript 判断标记是否忽略指定行上命中的规则
+     * 文件级标记对全文件生效，行级标记对所在行生效，单独成行的标记还对下一行生效
+     * @param line 命中的行号
+     * @param rule_id 命中的规则id
+     */
+    pub fn applies_to(&self, line: usize, rule_id: &str) -> bool {
+        let in_scope = self.file_level || self.line == line || (self.standalone && self.line + 1 == line);
+        in_scope && (self.all_rules || self.rule_ids.iter().any(|id| id == rule_id))
+    }
+
+    /**
+     * @descript 转换为命中结果中记录的忽略信息
+     */
+    pub fn to_suppression(&self) -> Suppression {
+        Suppression {
+            file_level: self.file_level,
+            line: self.line,
+            reason: self.reason.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{enums::LangType, util::lexer::Lexer};
+
+    use super::*;
+
+    // 解析源码最后一行中的标记，前面的行用于确定词法状态
+    fn parse_in(file_path: &str, lines: &[&str]) -> Option<SuppressionMarker> {
+        let known_rule_ids: HashSet<&str> = ["java-exec", "java-sql", "xss"].into_iter().collect();
+        let mut lexer = Lexer::for_file(&LangType::JAVA, file_path);
+        let (last, before) = lines.split_last()?;
+        for line in before {
+            lexer.next_line(line);
+        }
+        SuppressionMarker::parse(last, 10, &lexer.next_line(last), &known_rule_ids)
+    }
+
+    fn parse(line: &str) -> Option<SuppressionMarker> {
+        parse_in("Test.java", &[line])
+    }
+
+    #[test]
+    fn rule_list() {
+        let marker = parse("exec(cmd); // code_scan:ignore java-exec,java-sql 已校验参数").unwrap();
+        assert!(!marker.file_level);
+        assert!(!marker.all_rules);
+        assert!(!marker.standalone);
+        assert_eq!(marker.rule_ids, vec!["java-exec", "java-sql"]);
+        assert_eq!(marker.reason, "已校验参数");
+        assert!(marker.applies_to(10, "java-exec"));
+        assert!(marker.applies_to(10, "java-sql"));
+        assert!(!marker.applies_to(10, "xss"));
+        assert!(!marker.applies_to(11, "java-exec"));
+    }
+
+    #[test]
+    fn known_id_without_separator() {
+        let marker = parse("// code_scan:ignore xss").unwrap();
+        assert_eq!(marker.rule_ids, vec!["xss"]);
+        assert!(marker.reason.is_empty());
+    }
+
+    #[test]
+    fn all_rules() {
+        for line in ["// code_scan:ignore", "// code_scan:ignore * 测试代码", "// code_scan:ignore 测试代码"] {
+            let marker = parse(line).unwrap();
+            assert!(marker.all_rules, "{}", line);
+            assert!(marker.applies_to(10, "java-exec"), "{}", line);
+            assert!(marker.applies_to(10, "anything"), "{}", line);
+        }
+        assert_eq!(parse("// code_scan:ignore 测试代码").unwrap().reason, "测试代码");
+        assert_eq!(parse("// code_scan:ignore * 测试代码").unwrap().reason, "测试代码");
+    }
+
+    #[test]
+    fn unknown_rule_ids() {
+        let marker = parse("// code_scan:ignore java-exce 拼写错误").unwrap();
+        assert!(!marker.all_rules);
+        assert!(marker.rule_ids.is_empty());
+        assert_eq!(marker.unknown_rule_ids, vec!["java-exce"]);
+        assert!(!marker.applies_to(10, "java-exec"));
+        assert!(!marker.applies_to(11, "java-exec"));
+
+        let marker = parse("// code_scan:ignore java-exec,removed-rule").unwrap();
+        assert_eq!(marker.rule_ids, vec!["java-exec"]);
+        assert_eq!(marker.unknown_rule_ids, vec!["removed-rule"]);
+        assert!(marker.applies_to(10, "java-exec"));
+        assert!(!marker.applies_to(10, "java-sql"));
+    }
+
+    #[test]
+    fn scope() {
+        let marker = parse("    // code_scan:ignore java-exec").unwrap();
+        assert!(marker.standalone);
+        assert!(marker.applies_to(10, "java-exec"));
+        assert!(marker.applies_to(11, "java-exec"));
+        assert!(!marker.applies_to(12, "java-exec"));
+        assert!(!marker.applies_to(9, "java-exec"));
+
+        let marker = parse("/* code_scan:ignore-file java-sql 生成的代码 */").unwrap();
+        assert!(marker.file_level);
+        assert_eq!(marker.reason, "生成的代码");
+        assert!(marker.applies_to(1, "java-sql"));
+        assert!(marker.applies_to(500, "java-sql"));
+        assert!(!marker.applies_to(1, "java-exec"));
+
+        let marker = parse_in("Test.java", &["/**", " * code_scan:ignore"]).unwrap();
+        assert!(marker.standalone);
+        let marker = parse("/* a */ // code_scan:ignore").unwrap();
+        assert!(marker.standalone);
+        let marker = parse_in("index.html", &["<!-- code_scan:ignore xss -->"]).unwrap();
+        assert_eq!(marker.rule_ids, vec!["xss"]);
+        assert!(marker.reason.is_empty());
+    }
+
+    #[test]
+    fn not_a_marker() {
+        assert!(parse("exec(cmd);").is_none());
+        assert!(parse("String s = \"code_scan:ignore\";").is_none());
+        assert!(parse(" * code_scan:ignore").is_none());
+        assert!(parse("// code_scan:ignored").is_none());
+        assert!(parse("// code_scan:ignore-files").is_none());
+    }
+
+    #[test]
+    fn to_suppression() {
+        let suppression = parse("// code_scan:ignore-file 第三方代码").unwrap().to_suppression();
+        assert!(suppression.file_level);
+        assert_eq!(suppression.line, 10);
+        assert_eq!(suppression.reason, "第三方代码");
+    }
+
+    #[test]
+    fn marker_in_string() {
+        // 字符串中的注释符号和标记都不会生效
+        assert!(parse("log(\"#code_scan:ignore-file x\"); exec(cmd);").is_none());
+        assert!(parse("exec(\"http://h\", \"code_scan:ignore x\");").is_none());
+        assert!(parse("exec(\"/* code_scan:ignore \" + cmd);").is_none());
+        assert!(parse_in("Test.java", &["String sql = \"\"\"", "  -- code_scan:ignore-file"]).is_none());
+
+        // 字符串之后的注释中的标记仍然生效
+        let marker = parse("exec(\"code_scan:ignore\"); // code_scan:ignore java-exec").unwrap();
+        assert!(!marker.standalone);
+        assert_eq!(marker.rule_ids, vec!["java-exec"]);
+    }
+}